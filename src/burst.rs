@@ -0,0 +1,162 @@
+//! Typed CPU and IO bursts, and the sequences a process is made of.
+
+use std::collections::vec_deque::{self, VecDeque};
+
+/// One phase of a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Burst {
+    /// Time units to run on the CPU.
+    Cpu(i32),
+    /// Time units to wait on IO device `device`.
+    Io {
+        /// Index of the IO device.
+        device: usize,
+        /// Time units until the IO completes.
+        duration: i32,
+    },
+}
+
+impl Burst {
+    /// Time units left in this burst.
+    pub fn duration(&self) -> i32 {
+        match self {
+            Burst::Cpu(duration) | Burst::Io { duration, .. } => *duration,
+        }
+    }
+
+    /// True for a CPU burst.
+    pub fn is_cpu(&self) -> bool {
+        matches!(self, Burst::Cpu(_))
+    }
+}
+
+/// The bursts of a process, in the order it goes through them.
+///
+/// A sequence can only be built to start with a CPU burst, alternate CPU and IO
+/// bursts, and end with a CPU burst:
+///
+/// ```
+/// use process_scheduler_simulator::burst::{Burst, Bursts};
+///
+/// let bursts = Bursts::cpu(5).io(0, 27).cpu(3);
+/// assert_eq!(bursts, Bursts::alternating(5, &[(27, 3)]));
+/// assert_eq!(bursts.get(1), Some(&Burst::Io { device: 0, duration: 27 }));
+/// ```
+///
+/// Every burst lasts at least one time unit. The simulation consumes the sequence
+/// from the front, an empty sequence belongs to a process that has nothing left to
+/// do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bursts {
+    phases: VecDeque<Burst>,
+}
+
+/// A sequence that ends with an IO burst, only a CPU burst can follow it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AwaitingCpu {
+    phases: VecDeque<Burst>,
+}
+
+impl Bursts {
+    /// Sequence of a single CPU burst.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `duration` is not positive.
+    pub fn cpu(duration: i32) -> Bursts {
+        assert!(duration > 0, "burst duration must be positive");
+        Bursts {
+            phases: VecDeque::from(vec![Burst::Cpu(duration)]),
+        }
+    }
+
+    /// Sequence that starts with a `first` CPU burst, followed by `(io, cpu)`
+    /// pairs of bursts with all IO on device 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any duration is not positive.
+    pub fn alternating(first: i32, rest: &[(i32, i32)]) -> Bursts {
+        rest.iter().fold(Bursts::cpu(first), |bursts, &(io, cpu)| {
+            bursts.io(0, io).cpu(cpu)
+        })
+    }
+
+    /// Add an IO burst on `device`, which has to be followed by a CPU burst.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `duration` is not positive.
+    pub fn io(mut self, device: usize, duration: i32) -> AwaitingCpu {
+        assert!(duration > 0, "burst duration must be positive");
+        self.phases.push_back(Burst::Io { device, duration });
+        AwaitingCpu {
+            phases: self.phases,
+        }
+    }
+
+    /// Current burst.
+    pub fn front(&self) -> Option<&Burst> {
+        self.phases.front()
+    }
+
+    /// Burst `index` places after the current one.
+    pub fn get(&self, index: usize) -> Option<&Burst> {
+        self.phases.get(index)
+    }
+
+    /// Bursts from the current one onwards.
+    pub fn iter(&self) -> vec_deque::Iter<'_, Burst> {
+        self.phases.iter()
+    }
+
+    /// Number of bursts left, the current one included.
+    pub fn len(&self) -> usize {
+        self.phases.len()
+    }
+
+    /// True once the process went through all of its bursts.
+    pub fn is_empty(&self) -> bool {
+        self.phases.is_empty()
+    }
+
+    pub(crate) fn from_phases(phases: Vec<Burst>) -> Bursts {
+        // The phases of a validated workload, or none for a finished process.
+        Bursts {
+            phases: VecDeque::from(phases),
+        }
+    }
+
+    pub(crate) fn front_mut(&mut self) -> Option<&mut Burst> {
+        self.phases.front_mut()
+    }
+
+    pub(crate) fn pop_front(&mut self) -> Option<Burst> {
+        self.phases.pop_front()
+    }
+}
+
+impl AwaitingCpu {
+    /// Add the CPU burst that follows the IO burst.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `duration` is not positive.
+    pub fn cpu(mut self, duration: i32) -> Bursts {
+        assert!(duration > 0, "burst duration must be positive");
+        self.phases.push_back(Burst::Cpu(duration));
+        Bursts {
+            phases: self.phases,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Bursts {
+    type Item = &'a Burst;
+    type IntoIter = vec_deque::Iter<'a, Burst>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,1355 @@
+//! The simulation engine that drives a [`Scheduler`] over a workload.
+
+use crate::burst::Burst;
+use crate::error::SimError;
+use crate::event_queue::{Event, EventKind, EventQueue};
+use crate::observer::{Observer, SimEvent, SimView};
+use crate::process::{DeadlineOutcome, Process};
+use crate::result::{CpuMetrics, CpuRun, LoadSample, ShareMetrics, ShareSample, SimulationResult};
+use crate::scheduler::{Context, Overrun, Scheduler};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Settings of the simulated machine that apply whichever scheduler is used.
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Number of CPUs, which share the ready queue(s) of the scheduler.
+    pub cpus: usize,
+    /// Time charged every time a process is dispatched onto the CPU.
+    pub dispatch_latency: i32,
+    /// Extra time charged when the dispatched process is not the one that ran
+    /// last, e.g. for restoring its address space.
+    pub process_switch_cost: i32,
+    /// Extra time charged when the process that ran last is dispatched again.
+    pub redispatch_cost: i32,
+    /// Extra time charged when the dispatched process last ran on another CPU,
+    /// e.g. for warming up the cache of its new CPU.
+    pub migration_cost: i32,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> SimulationConfig {
+        SimulationConfig {
+            cpus: 1,
+            dispatch_latency: 0,
+            process_switch_cost: 0,
+            redispatch_cost: 0,
+            migration_cost: 0,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Overhead of dispatching a process, `same_process` when it is the one
+    /// that ran last on the CPU.
+    pub fn dispatch_cost(&self, same_process: bool) -> i32 {
+        if same_process {
+            self.dispatch_latency + self.redispatch_cost
+        } else {
+            self.dispatch_latency + self.process_switch_cost
+        }
+    }
+}
+
+/// Run processes in `processes` using `scheduler` on a machine with no context
+/// switch overhead and no observers, see [`simulate_with`].
+pub fn simulate(
+    scheduler: &mut dyn Scheduler,
+    processes: VecDeque<Process>,
+) -> Result<SimulationResult, SimError> {
+    simulate_with(scheduler, processes, &SimulationConfig::default(), &mut [])
+}
+
+/// Run processes in `processes` using `scheduler`, deliver every event to
+/// `observers` and return the results of the simulation.
+///
+/// The engine owns the global clock, the IO queue and the graveyard, the
+/// scheduler owns the ready queue(s) and decides which process runs next and
+/// for how long.
+///
+/// Every dispatch costs the overhead set in `config` before the process gets
+/// on the CPU. That time is neither idle nor useful CPU time.
+///
+/// The machine has as many CPUs as set in `config`. Whenever a CPU is free it runs
+/// the next process the scheduler picks for it, from ready queue(s) shared by all
+/// CPUs or kept per CPU (see [`PerCpu`](crate::scheduler::PerCpu)). A process that
+/// becomes ready while every CPU is busy interrupts the weakest running process it
+/// would take the CPU from. A process that gets on another CPU than the one it last
+/// ran on counts as a migration, and pays the migration cost of `config` on top of
+/// the context switch.
+///
+/// Fails on the first process whose bursts do not allow what the simulation
+/// asks of it, e.g. a process with no bursts at all. The processes are not
+/// validated up front, build them with [`workload::build`](crate::workload::build)
+/// to have every problem of a workload reported at once.
+///
+/// Simulation is event driven: the global clock jumps straight from one event
+/// (arrival, IO completion, quantum expiry, exit) to the next, so run time is
+/// proportional to the number of events rather than the number of time units.
+///
+/// # Panics
+///
+/// Panics if `config` has no CPUs.
+pub fn simulate_with(
+    scheduler: &mut dyn Scheduler,
+    processes: VecDeque<Process>,
+    config: &SimulationConfig,
+    observers: &mut [&mut dyn Observer],
+) -> Result<SimulationResult, SimError> {
+    assert!(config.cpus > 0, "machine must have at least one CPU");
+    let mut engine = Engine {
+        scheduler,
+        config,
+        observers,
+        global_clock: 0,
+        preemptions: 0,
+        events: EventQueue::new(),
+        io_queue: BTreeMap::new(),
+        graveyard: Vec::new(),
+        cpus: (0..config.cpus).map(|_| Cpu::default()).collect(),
+        arrived: Vec::new(),
+        returned: Vec::new(),
+        runs: Vec::new(),
+        loads: Vec::new(),
+        emitted: RefCell::new(Vec::new()),
+    };
+
+    // Processes enter the ready queue at their arrival time.
+    for process in processes {
+        engine
+            .events
+            .push(process.arrival_time, EventKind::Arrival(Box::new(process)));
+    }
+
+    engine.run()?;
+
+    let cpus = engine
+        .cpus
+        .iter()
+        .enumerate()
+        .map(|(index, cpu)| {
+            // Runs cut short before they started took no time on the CPU.
+            let timeline = engine
+                .runs
+                .iter()
+                .filter(|run| run.cpu == index && run.end > run.start)
+                .map(|run| CpuRun {
+                    process: run.process.clone(),
+                    start: run.start,
+                    end: run.end,
+                })
+                .collect();
+            CpuMetrics::new(
+                index,
+                engine.global_clock,
+                cpu.idle_time,
+                cpu.switch_overhead,
+                cpu.context_switches,
+                timeline,
+            )
+        })
+        .collect();
+    let mut result = SimulationResult::new(
+        engine.scheduler.name(),
+        engine.global_clock,
+        cpus,
+        engine.preemptions,
+        engine.graveyard,
+    );
+    if let Some(weights) = engine.scheduler.share_weights(&result.processes) {
+        let (shares, share_errors) = shares(&result.processes, &weights, &engine.runs);
+        result.shares = shares;
+        result.share_errors = share_errors;
+    }
+    result.loads = engine.loads;
+    Ok(result)
+}
+
+fn shares(
+    processes: &[Process],
+    weights: &[f64],
+    runs: &[Run],
+) -> (Vec<ShareMetrics>, Vec<ShareSample>) {
+    /*
+        Share of the CPU every process is entitled to by its weight against the
+        share it got while every process was in the system, from the arrival of
+        the last one to the exit of the first one, and how far off the shares were
+        at the end of every time on the CPU in between. Over the whole run if there
+        is no such time.
+    */
+    let mut from = processes
+        .iter()
+        .map(|process| process.arrival_time)
+        .max()
+        .unwrap_or(0);
+    let mut until = processes
+        .iter()
+        .map(|process| process.last_accessed + 1)
+        .min()
+        .unwrap_or(0);
+    if from >= until {
+        from = 0;
+        until = i32::MAX;
+    }
+
+    let total_weight: f64 = weights.iter().sum();
+    let entitled: Vec<f32> = weights
+        .iter()
+        .map(|weight| {
+            if total_weight > 0.0 {
+                (weight / total_weight) as f32
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let achieved = |cpu_times: &[i32]| -> Vec<f32> {
+        let total_cpu: i32 = cpu_times.iter().sum();
+        cpu_times
+            .iter()
+            .map(|&cpu_time| {
+                if total_cpu > 0 {
+                    cpu_time as f32 / total_cpu as f32
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    };
+
+    // Runs are in the order they ended.
+    let mut cpu_times = vec![0; processes.len()];
+    let mut samples: Vec<ShareSample> = Vec::new();
+    for run in runs.iter() {
+        let cpu_time = run.end.min(until) - run.start.max(from);
+        let index = processes
+            .iter()
+            .position(|process| process.name == run.process);
+        if let (true, Some(index)) = (cpu_time > 0, index) {
+            cpu_times[index] += cpu_time;
+            samples.push(ShareSample {
+                time: run.end.min(until),
+                errors: achieved(&cpu_times)
+                    .iter()
+                    .zip(entitled.iter())
+                    .map(|(achieved, entitled)| achieved - entitled)
+                    .collect(),
+            });
+        }
+    }
+
+    let shares = processes
+        .iter()
+        .zip(entitled.iter().zip(achieved(&cpu_times)))
+        .map(|(process, (&entitled_share, cpu_share))| ShareMetrics {
+            name: process.name.clone(),
+            entitled_share,
+            cpu_share,
+        })
+        .collect();
+    (shares, samples)
+}
+
+struct Engine<'a, 'b> {
+    scheduler: &'a mut dyn Scheduler,
+    config: &'a SimulationConfig,
+    observers: &'a mut [&'b mut dyn Observer],
+    global_clock: i32,
+    preemptions: usize,
+    events: EventQueue,
+    // Processes in IO state are saved here, keyed by the sequence number of their
+    // IO completion event, which keeps them in the order they started their IO burst.
+    io_queue: BTreeMap<u64, Process>,
+    // Processes that have completed are stored here.
+    graveyard: Vec<Process>,
+    cpus: Vec<Cpu>,
+    // Processes that became ready since the last scheduling decision.
+    arrived: Vec<Process>,
+    returned: Vec<u64>,
+    // Every time a process spent on the CPU.
+    runs: Vec<Run>,
+    // Load of every CPU whenever it changed, with ready queue(s) per CPU.
+    loads: Vec<LoadSample>,
+    // Events raised by the engine or the scheduler, not yet delivered to observers.
+    emitted: RefCell<Vec<SimEvent>>,
+}
+
+// A CPU of the machine, and what it did so far.
+#[derive(Default)]
+struct Cpu {
+    // Process currently on the CPU.
+    running: Option<Process>,
+    // Time slice of the running process. Only the event that ends it counts, the
+    // end of a slice cut short by a preemption is ignored.
+    slice: Option<Slice>,
+    // Name of the last process dispatched, to tell a switch from a re-dispatch.
+    last_dispatched: Option<String>,
+    idle_time: i32,
+    switch_overhead: i32,
+    context_switches: usize,
+}
+
+struct Slice {
+    start_time: i32,
+    end_time: i32,
+    seq: u64,
+}
+
+// What a process that became ready does to the running one.
+enum Interruption {
+    // Takes the CPU from it, which had this much of its time slice left.
+    Preempt(i32),
+    // Ends its time slice at this time instead.
+    CutSlice(i32),
+}
+
+// A running process that a process becoming ready could interrupt.
+struct Interruptible<'a> {
+    cpu: usize,
+    running: &'a Process,
+    // End of its time slice.
+    end_time: i32,
+    // Time left of its time slice, and of its CPU burst.
+    unused_time: i32,
+    remaining: i32,
+}
+
+struct Run {
+    cpu: usize,
+    process: String,
+    start: i32,
+    end: i32,
+}
+
+impl<'a, 'b> Engine<'a, 'b> {
+    fn run(&mut self) -> Result<(), SimError> {
+        while let Some(event) = self.events.pop() {
+            if self.is_stale(&event) {
+                continue;
+            }
+
+            // CPUs sat idle until this event.
+            for cpu in self.cpus.iter_mut().filter(|cpu| cpu.running.is_none()) {
+                cpu.idle_time += event.time - self.global_clock;
+            }
+            self.global_clock = event.time;
+
+            self.handle(event)?;
+
+            // Handle everything else that happens at this instant before scheduling,
+            // and leave the CPUs alone while a process is running on every one. The
+            // end of a slice cut short earlier is no reason to wait.
+            self.drop_stale();
+            if self.events.peek_time() == Some(self.global_clock)
+                || self.cpus.iter().all(|cpu| cpu.running.is_some())
+            {
+                continue;
+            }
+
+            self.admit_ready();
+            self.dispatch()?;
+            self.sample_loads();
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, event: Event) -> Result<(), SimError> {
+        let global_clock = self.global_clock;
+        let cpu = self.cpu_of(&event);
+        match event.kind {
+            // The process is ready from now on, even if the scheduler only gets it at
+            // the next scheduling decision.
+            EventKind::Arrival(mut process) => {
+                process.ready_since = Some(global_clock);
+                self.emit(SimEvent::Arrival {
+                    time: global_clock,
+                    process: process.name.clone(),
+                });
+                let interruption = self.interruption(&process);
+                let name = process.name.clone();
+                self.arrived.push(*process);
+                self.interrupt(interruption, name)?;
+            }
+            // Process stays in the IO queue until the next scheduling decision.
+            EventKind::IoComplete => {
+                let process = self.io_queue.get_mut(&event.seq).unwrap();
+                process.ready_since = Some(global_clock);
+                let process = &self.io_queue[&event.seq];
+                let name = process.name.clone();
+                let interruption = self.interruption(process);
+                self.returned.push(event.seq);
+                self.emit(SimEvent::IoComplete {
+                    time: global_clock,
+                    process: name.clone(),
+                });
+                self.interrupt(interruption, name)?;
+            }
+            EventKind::QuantumExpired => {
+                let mut process = self.take_running(cpu.unwrap());
+                process.ready_since = Some(global_clock);
+                self.emit(SimEvent::QuantumExpired {
+                    time: global_clock,
+                    process: process.name.clone(),
+                });
+                self.scheduler.on_quantum_expired(
+                    process,
+                    &Context::new(global_clock, &self.io_queue, &self.emitted),
+                );
+            }
+            EventKind::BurstComplete | EventKind::Exit => {
+                let mut process = self.take_running(cpu.unwrap());
+                self.scheduler.on_burst_complete(
+                    &process,
+                    &Context::new(global_clock, &self.io_queue, &self.emitted),
+                );
+                self.meet_deadlines(&mut process);
+                self.finish_burst(process)?;
+            }
+            EventKind::Overrun => {
+                let process = self.take_running(cpu.unwrap());
+                self.drop_late(process)?;
+            }
+        }
+        self.flush();
+        Ok(())
+    }
+
+    fn finish_burst(&mut self, mut process: Process) -> Result<(), SimError> {
+        /*
+           Move a process done with its CPU burst on to its IO burst, or out of the
+           system after its last one.
+        */
+        let global_clock = self.global_clock;
+        if !process.has_io_next() {
+            process.ready_next_io(global_clock)?;
+            self.emit(SimEvent::Exit {
+                time: global_clock,
+                process: process.name.clone(),
+            });
+            self.graveyard.push(process);
+            return Ok(());
+        }
+
+        process.ready_next_io(global_clock)?;
+        process.calc_return_time(global_clock)?;
+
+        let device = match process.bursts().front() {
+            Some(Burst::Io { device, .. }) => *device,
+            _ => 0,
+        };
+        self.emit(SimEvent::IoStart {
+            time: global_clock,
+            process: process.name.clone(),
+            device,
+            return_time: process.return_from_io_time,
+        });
+
+        process.ready_next_cpu(global_clock)?;
+        let seq = self
+            .events
+            .push(process.return_from_io_time, EventKind::IoComplete);
+        self.io_queue.insert(seq, process);
+        Ok(())
+    }
+
+    fn meet_deadlines(&mut self, process: &mut Process) {
+        /*
+           Record the deadline of the CPU burst the process just completed, and the
+           deadline of its job after its last CPU burst.
+        */
+        let mut deadlines: Vec<i32> = process
+            .params
+            .burst_deadlines
+            .front()
+            .copied()
+            .into_iter()
+            .collect();
+        if !process.has_io_next() {
+            deadlines.extend(process.params.deadline);
+        }
+
+        for deadline in deadlines {
+            let outcome = DeadlineOutcome {
+                deadline,
+                completion: self.global_clock,
+                dropped: false,
+            };
+            if outcome.missed() {
+                self.emit(SimEvent::DeadlineMiss {
+                    time: self.global_clock,
+                    process: process.name.clone(),
+                    deadline,
+                });
+            }
+            process.deadline_outcomes.push(outcome);
+        }
+    }
+
+    fn drop_late(&mut self, mut process: Process) -> Result<(), SimError> {
+        /*
+           Drop the CPU burst of a process past its deadline, or the whole process,
+           as the scheduler asks.
+        */
+        let global_clock = self.global_clock;
+        let deadline = process.current_deadline().unwrap_or(global_clock);
+        process.deadline_outcomes.push(DeadlineOutcome {
+            deadline,
+            completion: global_clock,
+            dropped: true,
+        });
+        self.emit(SimEvent::DeadlineMiss {
+            time: global_clock,
+            process: process.name.clone(),
+            deadline,
+        });
+        self.scheduler.on_burst_complete(
+            &process,
+            &Context::new(global_clock, &self.io_queue, &self.emitted),
+        );
+
+        // Only a burst deadline leaves anything to move on to.
+        if self.scheduler.overrun() == Overrun::SkipBurst
+            && !process.params.burst_deadlines.is_empty()
+        {
+            process.skip_burst(global_clock)?;
+            return self.finish_burst(process);
+        }
+
+        process.abort(global_clock);
+        self.emit(SimEvent::Exit {
+            time: global_clock,
+            process: process.name.clone(),
+        });
+        self.graveyard.push(process);
+        Ok(())
+    }
+
+    fn admit_ready(&mut self) {
+        /*
+           Send new processes, then processes done with IO, into the ready queue.
+        */
+        let global_clock = self.global_clock;
+        for process in std::mem::take(&mut self.arrived) {
+            self.scheduler.on_ready(
+                process,
+                &Context::new(global_clock, &self.io_queue, &self.emitted),
+            );
+        }
+
+        // Processes the scheduler considers equal keep the order they started IO.
+        self.returned.sort_unstable();
+        let mut returning: Vec<Process> = Vec::new();
+        for seq in self.returned.drain(..) {
+            returning.push(self.io_queue.remove(&seq).unwrap());
+        }
+        let scheduler = &self.scheduler;
+        returning.sort_by(|a, b| scheduler.io_return_order(a, b));
+        for process in returning {
+            self.scheduler.on_ready(
+                process,
+                &Context::new(global_clock, &self.io_queue, &self.emitted),
+            );
+        }
+        self.flush();
+    }
+
+    fn dispatch(&mut self) -> Result<(), SimError> {
+        /*
+           Run the processes chosen by the scheduler on the idle CPUs, and schedule
+           the end of their time slices. CPUs left without one idle until the next
+           event.
+        */
+        let global_clock = self.global_clock;
+        let idle: Vec<usize> = self.idle_cpus().map(|(cpu, _)| cpu).collect();
+        for cpu in idle {
+            match self.pick(cpu)? {
+                Some((process, time_quanta)) => self.run_on(cpu, process, time_quanta)?,
+                None => {
+                    if let Some(until) = self.events.peek_time() {
+                        self.emit(SimEvent::Idle {
+                            time: global_clock,
+                            cpu,
+                            until,
+                        });
+                        self.flush();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn sample_loads(&mut self) {
+        /*
+           Record the load of every CPU, the ready processes queued for it and the
+           one running on it, when it changed. Only the last change at an instant
+           counts.
+        */
+        let mut loads = match self.scheduler.cpu_loads() {
+            Some(loads) => loads,
+            None => return,
+        };
+        loads.resize(self.cpus.len(), 0);
+        for (load, cpu) in loads.iter_mut().zip(self.cpus.iter()) {
+            if cpu.running.is_some() {
+                *load += 1;
+            }
+        }
+
+        if self
+            .loads
+            .last()
+            .is_some_and(|sample| sample.loads == loads)
+        {
+            return;
+        }
+        if self
+            .loads
+            .last()
+            .is_some_and(|sample| sample.time == self.global_clock)
+        {
+            self.loads.pop();
+        }
+        self.loads.push(LoadSample {
+            time: self.global_clock,
+            loads,
+        });
+    }
+
+    fn idle_cpus(&self) -> impl Iterator<Item = (usize, &Cpu)> {
+        self.cpus
+            .iter()
+            .enumerate()
+            .filter(|(_, cpu)| cpu.running.is_none())
+    }
+
+    fn pick(&mut self, cpu: usize) -> Result<Option<(Process, i32)>, SimError> {
+        /*
+           Next process the scheduler picks to run on `cpu` and its time-quanta,
+           None if there is nothing to run.
+        */
+        let global_clock = self.global_clock;
+        let overrun = self.scheduler.overrun();
+        loop {
+            let picked = self
+                .scheduler
+                .pick_next(&Context::new(global_clock, &self.io_queue, &self.emitted).on_cpu(cpu));
+            self.flush();
+
+            let (mut process, time_quanta) = match picked {
+                Some(picked) => picked,
+                None => return Ok(None),
+            };
+
+            // The process leaves the ready queue.
+            if let Some(ready_since) = process.ready_since.take() {
+                process.longest_wait = process.longest_wait.max(global_clock - ready_since);
+            }
+
+            // Late work is dropped rather than run, unless the scheduler lets it
+            // continue. Then the scheduler picks again.
+            let late = process
+                .current_deadline()
+                .is_some_and(|deadline| deadline <= global_clock);
+            if overrun != Overrun::Continue && late {
+                self.drop_late(process)?;
+                self.flush();
+                continue;
+            }
+            return Ok(Some((process, time_quanta)));
+        }
+    }
+
+    fn run_on(
+        &mut self,
+        cpu: usize,
+        mut process: Process,
+        time_quanta: i32,
+    ) -> Result<(), SimError> {
+        /*
+           Run `process` on CPU `cpu` for `time_quanta` at most, and schedule the end
+           of its time slice.
+        */
+        let global_clock = self.global_clock;
+        let overrun = self.scheduler.overrun();
+
+        // Charge the context switch and the migration, if any, the process starts
+        // running once they are done.
+        let same_process = self.cpus[cpu].last_dispatched.as_ref() == Some(&process.name);
+        let migrated_from = process.last_cpu.filter(|&last_cpu| last_cpu != cpu);
+        let mut dispatch_cost = self.config.dispatch_cost(same_process);
+        if migrated_from.is_some() {
+            process.migrations += 1;
+            dispatch_cost += self.config.migration_cost;
+        }
+        process.last_cpu = Some(cpu);
+        self.cpus[cpu].switch_overhead += dispatch_cost;
+        if !same_process {
+            self.cpus[cpu].context_switches += 1;
+        }
+        self.cpus[cpu].last_dispatched = Some(process.name.clone());
+        let start_time = global_clock + dispatch_cost;
+
+        // Late work is dropped at its deadline, if it comes before the end of the
+        // time-quanta.
+        let deadline = process
+            .current_deadline()
+            .filter(|_| overrun != Overrun::Continue);
+        let run_time = match deadline {
+            Some(deadline) if deadline - start_time < time_quanta => (deadline - start_time).max(0),
+            _ => time_quanta,
+        };
+
+        // Run process using time-quanta and schedule the end of its time slice.
+        let left_over = process.run(run_time, start_time)?;
+        let end_time = start_time + run_time - left_over;
+
+        // Process is not guaranteed to have completed its burst, or to have
+        // an IO burst to move on to.
+        let kind = if !process.burst_completed && run_time < time_quanta {
+            EventKind::Overrun
+        } else if !process.burst_completed {
+            EventKind::QuantumExpired
+        } else if process.has_io_next() {
+            EventKind::BurstComplete
+        } else {
+            EventKind::Exit
+        };
+        let seq = self.events.push(end_time, kind);
+        self.cpus[cpu].slice = Some(Slice {
+            start_time,
+            end_time,
+            seq,
+        });
+
+        if let Some(from) = migrated_from {
+            self.emit(SimEvent::Migrate {
+                time: global_clock,
+                process: process.name.clone(),
+                from,
+                to: cpu,
+            });
+        }
+        self.emit(SimEvent::Dispatch {
+            time: global_clock,
+            cpu,
+            process: process.name.clone(),
+            time_quanta,
+            switch_cost: dispatch_cost,
+        });
+        self.cpus[cpu].running = Some(process);
+        self.flush();
+        Ok(())
+    }
+
+    fn cpu_of(&self, event: &Event) -> Option<usize> {
+        /*
+           CPU whose time slice the event ends, if it is the running one there.
+        */
+        self.cpus
+            .iter()
+            .position(|cpu| cpu.slice.as_ref().map(|slice| slice.seq) == Some(event.seq))
+    }
+
+    fn is_stale(&self, event: &Event) -> bool {
+        /*
+           The end of a time slice other than a running one.
+        */
+        match event.kind {
+            EventKind::QuantumExpired
+            | EventKind::BurstComplete
+            | EventKind::Exit
+            | EventKind::Overrun => self.cpu_of(event).is_none(),
+            EventKind::Arrival(_) | EventKind::IoComplete => false,
+        }
+    }
+
+    fn drop_stale(&mut self) {
+        /*
+           Drop stale events from the front of the event queue.
+        */
+        while self.events.peek().is_some_and(|event| self.is_stale(event)) {
+            self.events.pop();
+        }
+    }
+
+    fn take_running(&mut self, cpu: usize) -> Process {
+        let process = self.cpus[cpu].running.take().unwrap();
+        if let Some(slice) = self.cpus[cpu].slice.take() {
+            self.runs.push(Run {
+                cpu,
+                process: process.name.clone(),
+                start: slice.start_time,
+                end: self.global_clock,
+            });
+        }
+        process
+    }
+
+    fn interruption(&self, ready: &Process) -> Option<(usize, Interruption)> {
+        /*
+           What `ready` does to the running processes, and on which CPU. A process
+           is only interrupted once it actually started running, and not on the
+           last unit of its time slice. No process is while there is an idle CPU
+           left for every process that became ready.
+        */
+        let waiting = self.arrived.len() + self.returned.len();
+        if self.idle_cpus().count() > waiting {
+            return None;
+        }
+
+        let global_clock = self.global_clock;
+        let interruptible: Vec<Interruptible> = self
+            .cpus
+            .iter()
+            .enumerate()
+            .filter_map(|(cpu, state)| {
+                let running = state.running.as_ref()?;
+                let slice = state.slice.as_ref()?;
+                if global_clock <= slice.start_time || global_clock >= slice.end_time {
+                    return None;
+                }
+                let unused_time = slice.end_time - global_clock;
+                Some(Interruptible {
+                    cpu,
+                    running,
+                    end_time: slice.end_time,
+                    unused_time,
+                    remaining: running.current_burst().unwrap_or(0).max(0) + unused_time,
+                })
+            })
+            .collect();
+
+        // Of the processes `ready` takes the CPU from, the one that the others
+        // would take the CPU from as well, were they ready with what they have left.
+        let preempted = interruptible
+            .iter()
+            .filter(|other| {
+                self.scheduler
+                    .preempts(other.running, other.remaining, ready)
+            })
+            .reduce(|weakest, other| {
+                let mut stronger = weakest.running.clone();
+                match stronger.preempt(weakest.unused_time, global_clock) {
+                    Ok(())
+                        if self
+                            .scheduler
+                            .preempts(other.running, other.remaining, &stronger) =>
+                    {
+                        other
+                    }
+                    _ => weakest,
+                }
+            });
+        if let Some(preempted) = preempted {
+            return Some((preempted.cpu, Interruption::Preempt(preempted.unused_time)));
+        }
+
+        // Otherwise the time slice that ends first once cut.
+        interruptible
+            .iter()
+            .filter_map(|other| {
+                self.scheduler
+                    .cut_slice(other.running, other.remaining, ready)
+                    .map(|time| global_clock + time.max(1))
+                    .filter(|&end_time| end_time < other.end_time)
+                    .map(|end_time| (other.cpu, end_time))
+            })
+            .min_by_key(|&(_, end_time)| end_time)
+            .map(|(cpu, end_time)| (cpu, Interruption::CutSlice(end_time)))
+    }
+
+    fn interrupt(
+        &mut self,
+        interruption: Option<(usize, Interruption)>,
+        by: String,
+    ) -> Result<(), SimError> {
+        match interruption {
+            Some((cpu, Interruption::Preempt(unused_time))) => self.preempt(cpu, unused_time, by),
+            Some((cpu, Interruption::CutSlice(end_time))) => self.cut_slice(cpu, end_time),
+            None => Ok(()),
+        }
+    }
+
+    fn cut_slice(&mut self, cpu: usize, end_time: i32) -> Result<(), SimError> {
+        /*
+           End the time slice of the process running on `cpu` at `end_time` rather
+           than when it was meant to, the time left after it goes back on its CPU
+           burst.
+        */
+        let Cpu { running, slice, .. } = &mut self.cpus[cpu];
+        let slice = slice.as_mut().unwrap();
+        let process = running.as_mut().unwrap();
+        process.preempt(slice.end_time - end_time, end_time)?;
+        slice.end_time = end_time;
+        slice.seq = self.events.push(end_time, EventKind::QuantumExpired);
+        Ok(())
+    }
+
+    fn preempt(&mut self, cpu: usize, unused_time: i32, by: String) -> Result<(), SimError> {
+        /*
+           Take the process running on `cpu` off it and hand it back to the
+           scheduler.
+        */
+        let global_clock = self.global_clock;
+        let mut process = self.take_running(cpu);
+        process.preempt(unused_time, global_clock)?;
+        process.ready_since = Some(global_clock);
+        self.preemptions += 1;
+
+        self.emit(SimEvent::Preempt {
+            time: global_clock,
+            process: process.name.clone(),
+            by,
+        });
+        self.scheduler.on_preempted(
+            process,
+            &Context::new(global_clock, &self.io_queue, &self.emitted),
+        );
+        Ok(())
+    }
+
+    fn emit(&self, event: SimEvent) {
+        self.emitted.borrow_mut().push(event);
+    }
+
+    fn flush(&mut self) {
+        /*
+           Deliver the events raised so far to every observer.
+        */
+        let events: Vec<SimEvent> = self.emitted.borrow_mut().drain(..).collect();
+        if events.is_empty() || self.observers.is_empty() {
+            return;
+        }
+
+        let view = SimView {
+            global_clock: self.global_clock,
+            scheduler: &*self.scheduler,
+            io_queue: &self.io_queue,
+            running: self.cpus.iter().map(|cpu| cpu.running.as_ref()).collect(),
+        };
+        for event in events.iter() {
+            for observer in self.observers.iter_mut() {
+                observer.on_event(event, &view);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::error::Operation;
+    use crate::observer::EventLog;
+    use crate::process::SchedulingParams;
+    use crate::scheduler::{Fcfs, Mlfq, PerCpu, Policy, Priority, Srtf};
+    use crate::workload::default_workload;
+
+    #[test]
+    fn simulate_reports_metrics() {
+        let processes = VecDeque::from(vec![
+            Process::new("P1", Bursts::alternating(3, &[(10, 2)])),
+            Process::new("P2", Bursts::cpu(4)),
+        ]);
+
+        let result = simulate(&mut Fcfs::new(), processes).unwrap();
+
+        // P1 runs 0-3, P2 runs 3-7, CPU idles 7-13 until P1 is back from IO.
+        assert_eq!(result.global_clock, 15);
+        assert_eq!(result.idle_time, 6);
+        assert_eq!(result.cpu_utilization, 0.6);
+        assert_eq!(result.processes.len(), 2);
+
+        let p2 = &result.metrics[1];
+        assert_eq!(p2.name, "P2");
+        assert_eq!(p2.response_time, 3);
+        assert_eq!(p2.turnaround_time, 6);
+        assert_eq!(p2.waiting_time, 2);
+    }
+
+    #[test]
+    fn simulate_waits_for_arrival() {
+        let processes = VecDeque::from(vec![
+            Process::new("P1", Bursts::cpu(4)),
+            Process::new("P2", Bursts::cpu(2)).with_arrival_time(2),
+            Process::new("P3", Bursts::cpu(3)).with_arrival_time(10),
+        ]);
+
+        let result = simulate(&mut Fcfs::new(), processes).unwrap();
+
+        // P1 runs 0-4, P2 runs 4-6, CPU idles 6-10 until P3 arrives.
+        assert_eq!(result.global_clock, 13);
+        assert_eq!(result.idle_time, 4);
+
+        let p2 = &result.metrics[1];
+        assert_eq!(p2.response_time, 2);
+        assert_eq!(p2.turnaround_time, 3);
+
+        let p3 = &result.metrics[2];
+        assert_eq!(p3.response_time, 0);
+        assert_eq!(p3.turnaround_time, 2);
+    }
+
+    #[test]
+    fn simulate_charges_context_switches() {
+        let config = SimulationConfig {
+            dispatch_latency: 1,
+            process_switch_cost: 2,
+            ..Default::default()
+        };
+        let processes = VecDeque::from(vec![
+            Process::new("P1", Bursts::cpu(12)),
+            Process::new("P2", Bursts::cpu(2)).with_arrival_time(20),
+        ]);
+
+        let result = simulate_with(&mut Mlfq::new(), processes, &config, &mut []).unwrap();
+
+        // P1 runs 3-8 after a switch, is re-dispatched and runs 9-16. CPU idles
+        // 16-20, then P2 runs 23-25 after a switch.
+        assert_eq!(result.global_clock, 25);
+        assert_eq!(result.switch_overhead, 7);
+        assert_eq!(result.idle_time, 4);
+        assert_eq!(result.cpu_utilization, 14.0 / 25.0);
+        assert_eq!(result.metrics[0].response_time, 3);
+        assert_eq!(result.metrics[1].response_time, 3);
+    }
+
+    #[test]
+    fn simulate_reports_malformed_process() {
+        let processes = VecDeque::from(vec![
+            Process::new("P1", Bursts::alternating(3, &[(10, 2)])),
+            Process::new("P2", Bursts::from_phases(Vec::new())),
+        ]);
+
+        let error = simulate(&mut Fcfs::new(), processes).unwrap_err();
+
+        assert_eq!(
+            error,
+            SimError::NoBurst {
+                process: "P2".to_string(),
+                operation: Operation::Run,
+                global_clock: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn simulate_jumps_over_long_io() {
+        let processes = VecDeque::from(vec![Process::new(
+            "P1",
+            Bursts::alternating(1, &[(500_000_000, 1)]),
+        )]);
+
+        let result = simulate(&mut Fcfs::new(), processes).unwrap();
+
+        assert_eq!(result.global_clock, 500_000_002);
+        assert_eq!(result.idle_time, 500_000_000);
+    }
+
+    #[test]
+    fn preemption_as_an_earlier_slice_would_have_ended() {
+        let processes = VecDeque::from(vec![
+            Process::new("L", Bursts::cpu(10)).with_params(SchedulingParams {
+                priority: 3,
+                ..Default::default()
+            }),
+            Process::new("H", Bursts::cpu(3))
+                .with_params(SchedulingParams {
+                    priority: 1,
+                    ..Default::default()
+                })
+                .with_arrival_time(2),
+            Process::new("M", Bursts::cpu(1))
+                .with_params(SchedulingParams {
+                    priority: 2,
+                    ..Default::default()
+                })
+                .with_arrival_time(10),
+        ]);
+
+        // L 0-2, H 2-5, L 5-10, M 10-11 although the first slice of L would have
+        // ended at 10, L 11-14.
+        let result = simulate(&mut Priority::preemptive(), processes).unwrap();
+        assert_eq!(result.preemptions, 2);
+        assert_eq!(result.idle_time, 0);
+        assert_eq!(result.global_clock, 14);
+        assert_eq!(result.metrics[2].response_time, 0);
+    }
+
+    #[test]
+    fn cpus_share_a_global_ready_queue() {
+        let config = SimulationConfig {
+            cpus: 2,
+            ..Default::default()
+        };
+        let processes = VecDeque::from(vec![
+            Process::new("P1", Bursts::cpu(6)),
+            Process::new("P2", Bursts::cpu(4)),
+            Process::new("P3", Bursts::cpu(3)),
+        ]);
+
+        let result = simulate_with(&mut Fcfs::new(), processes, &config, &mut []).unwrap();
+
+        // P1 runs 0-6 on CPU 0, P2 0-4 and P3 4-7 on CPU 1, CPU 0 idles 6-7.
+        let timeline = |cpu: &CpuMetrics| -> Vec<(String, i32, i32)> {
+            cpu.timeline
+                .iter()
+                .map(|run| (run.process.clone(), run.start, run.end))
+                .collect()
+        };
+        assert_eq!(result.global_clock, 7);
+        assert_eq!(result.metrics[2].response_time, 4);
+        assert_eq!(result.cpus.len(), 2);
+        assert_eq!(timeline(&result.cpus[0]), vec![("P1".to_string(), 0, 6)]);
+        assert_eq!(
+            timeline(&result.cpus[1]),
+            vec![("P2".to_string(), 0, 4), ("P3".to_string(), 4, 7)]
+        );
+        assert_eq!(result.cpus[0].idle_time, 1);
+        assert_eq!(result.cpus[0].utilization, 6.0 / 7.0);
+        assert_eq!(result.cpus[1].context_switches, 2);
+        assert_eq!(result.cpus[1].utilization, 1.0);
+        assert_eq!(result.idle_time, 1);
+        assert_eq!(result.cpu_utilization, 13.0 / 14.0);
+    }
+
+    #[test]
+    fn preemption_takes_the_cpu_from_the_weakest_process() {
+        let config = SimulationConfig {
+            cpus: 2,
+            ..Default::default()
+        };
+        let processes = VecDeque::from(vec![
+            Process::new("P1", Bursts::cpu(10)),
+            Process::new("P2", Bursts::cpu(20)),
+            Process::new("P3", Bursts::cpu(2)).with_arrival_time(3),
+        ]);
+
+        // P3 has less left than both running processes, and takes the CPU of P2,
+        // which has the most left, until 5.
+        let mut log = EventLog::default();
+        let result = simulate_with(&mut Srtf::new(), processes, &config, &mut [&mut log]).unwrap();
+        assert_eq!(result.global_clock, 22);
+        assert_eq!(result.preemptions, 1);
+        assert_eq!(result.metrics[0].turnaround_time, 9);
+        assert!(log.events.contains(&SimEvent::Preempt {
+            time: 3,
+            process: "P2".to_string(),
+            by: "P3".to_string()
+        }));
+        assert!(log.events.contains(&SimEvent::Dispatch {
+            time: 3,
+            cpu: 1,
+            process: "P3".to_string(),
+            time_quanta: 2,
+            switch_cost: 0
+        }));
+    }
+
+    #[test]
+    fn every_policy_runs_on_several_cpus() {
+        // The same work gets done sooner, and no process is on two CPUs at once.
+        let config = SimulationConfig {
+            cpus: 2,
+            ..Default::default()
+        };
+        for policy in Policy::ALL.iter() {
+            let single = policy.run(default_workload()).unwrap();
+            let result = policy
+                .run_with(default_workload(), &config, &mut [])
+                .unwrap();
+            assert!(
+                result.global_clock < single.global_clock,
+                "{}",
+                policy.label()
+            );
+
+            let mut runs: Vec<&CpuRun> = result
+                .cpus
+                .iter()
+                .flat_map(|cpu| cpu.timeline.iter())
+                .collect();
+            let busy: i32 = runs.iter().map(|run| run.end - run.start).sum();
+            let total: i32 = single.cpus.iter().map(|cpu| cpu.busy_time()).sum();
+            assert_eq!(busy, total, "{}", policy.label());
+
+            runs.sort_by_key(|run| (run.process.clone(), run.start));
+            for pair in runs.windows(2) {
+                if pair[0].process == pair[1].process {
+                    assert!(pair[0].end <= pair[1].start, "{}", policy.label());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn migrate_events_match_the_migrations_counted() {
+        let config = SimulationConfig {
+            cpus: 2,
+            migration_cost: 1,
+            ..Default::default()
+        };
+        let mut total = 0;
+        for policy in Policy::ALL.iter() {
+            let mut global = policy.scheduler();
+            let mut per_cpu = PerCpu::new(2, || policy.scheduler());
+            let schedulers: [&mut dyn Scheduler; 2] = [global.as_mut(), &mut per_cpu];
+            for scheduler in schedulers {
+                let mut log = EventLog::default();
+                let result =
+                    simulate_with(scheduler, default_workload(), &config, &mut [&mut log]).unwrap();
+                for metrics in result.metrics.iter() {
+                    let events = log
+                        .events
+                        .iter()
+                        .filter(|event| {
+                            matches!(event, SimEvent::Migrate { process, .. } if *process == metrics.name)
+                        })
+                        .count();
+                    assert_eq!(events, metrics.migrations, "{}", result.scheduler);
+                }
+                total += result.migrations();
+            }
+        }
+        assert!(total > 0);
+    }
+
+    #[test]
+    fn every_policy_reports_the_same_processes() {
+        // Reports of all policies on a workload compare process by process: the
+        // time every process spent on the CPU and in IO is the same.
+        let fcfs = Policy::Fcfs.run(default_workload()).unwrap();
+        let busy = |result: &SimulationResult| -> Vec<(String, i32)> {
+            result
+                .metrics
+                .iter()
+                .map(|metrics| {
+                    (
+                        metrics.name.clone(),
+                        metrics.turnaround_time - metrics.waiting_time,
+                    )
+                })
+                .collect()
+        };
+        for policy in Policy::ALL.iter() {
+            let result = policy.run(default_workload()).unwrap();
+            assert_eq!(busy(&result), busy(&fcfs), "{}", policy.label());
+        }
+    }
+
+    #[test]
+    fn simulate_delivers_events() {
+        let processes = VecDeque::from(vec![Process::new("P1", Bursts::cpu(20).io(0, 4).cpu(1))]);
+
+        let mut log = EventLog::default();
+        simulate_with(
+            &mut Mlfq::new(),
+            processes,
+            &SimulationConfig::default(),
+            &mut [&mut log],
+        )
+        .unwrap();
+
+        let process = "P1".to_string();
+        assert_eq!(
+            log.events,
+            vec![
+                SimEvent::Arrival {
+                    time: 0,
+                    process: process.clone()
+                },
+                SimEvent::Dispatch {
+                    time: 0,
+                    cpu: 0,
+                    process: process.clone(),
+                    time_quanta: 5,
+                    switch_cost: 0
+                },
+                SimEvent::QuantumExpired {
+                    time: 5,
+                    process: process.clone()
+                },
+                SimEvent::Demote {
+                    time: 5,
+                    process: process.clone(),
+                    from: 0,
+                    to: 1
+                },
+                SimEvent::Dispatch {
+                    time: 5,
+                    cpu: 0,
+                    process: process.clone(),
+                    time_quanta: 10,
+                    switch_cost: 0
+                },
+                SimEvent::QuantumExpired {
+                    time: 15,
+                    process: process.clone()
+                },
+                SimEvent::Demote {
+                    time: 15,
+                    process: process.clone(),
+                    from: 1,
+                    to: 2
+                },
+                SimEvent::Dispatch {
+                    time: 15,
+                    cpu: 0,
+                    process: process.clone(),
+                    time_quanta: 5,
+                    switch_cost: 0
+                },
+                SimEvent::IoStart {
+                    time: 20,
+                    process: process.clone(),
+                    device: 0,
+                    return_time: 24
+                },
+                SimEvent::Idle {
+                    time: 20,
+                    cpu: 0,
+                    until: 24
+                },
+                SimEvent::IoComplete {
+                    time: 24,
+                    process: process.clone()
+                },
+                SimEvent::Dispatch {
+                    time: 24,
+                    cpu: 0,
+                    process: process.clone(),
+                    time_quanta: 5,
+                    switch_cost: 0
+                },
+                SimEvent::Exit { time: 25, process },
+            ]
+        );
+    }
+
+    #[test]
+    fn default_workload_results() {
+        // Reference results of the original, hand-written scheduler loops, but for
+        // the MLFQ waiting and turnaround times, see below.
+        let fcfs = Policy::Fcfs.run(default_workload()).unwrap();
+        assert_eq!(fcfs.global_clock, 672);
+        assert_eq!(fcfs.idle_time, 118);
+        assert_eq!(fcfs.average_turnaround_time(), 534.125);
+
+        let sjf = Policy::Sjf.run(default_workload()).unwrap();
+        assert_eq!(sjf.global_clock, 630);
+        assert_eq!(sjf.idle_time, 76);
+        assert_eq!(sjf.average_turnaround_time(), 468.625);
+
+        let mlfq = Policy::Mlfq.run(default_workload()).unwrap();
+        assert_eq!(mlfq.global_clock, 621);
+        assert_eq!(mlfq.idle_time, 67);
+        // The original loops counted the unused part of a time-quanta as run time
+        // when a burst completed early, which put its end, and so the turnaround,
+        // too late. Fixed in Process::run, this lowers both averages by 10 from the
+        // 187.5 and 523.75 of the original loops. FCFS and SJF always run whole
+        // bursts, and are not affected.
+        assert_eq!(mlfq.average_waiting_time(), 177.5);
+        assert_eq!(mlfq.average_turnaround_time(), 513.75);
+    }
+}
@@ -0,0 +1,248 @@
+//! Errors raised while validating or simulating a malformed workload.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Operation on a [`Process`](crate::Process) that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Running the current CPU burst, see `Process::run`.
+    Run,
+    /// Calculating the return time of an IO burst, see `Process::calc_return_time`.
+    CalcReturnTime,
+    /// Moving from a CPU burst to the next IO burst, see `Process::ready_next_io`.
+    ReadyNextIo,
+    /// Moving from an IO burst to the next CPU burst, see `Process::ready_next_cpu`.
+    ReadyNextCpu,
+    /// Giving back the unused part of a time-quanta, see `Process::preempt`.
+    Preempt,
+    /// Dropping the rest of a CPU burst past its deadline, see `Process::skip_burst`.
+    SkipBurst,
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Operation::Run => "run",
+            Operation::CalcReturnTime => "calc_return_time",
+            Operation::ReadyNextIo => "ready_next_io",
+            Operation::ReadyNextCpu => "ready_next_cpu",
+            Operation::Preempt => "preempt",
+            Operation::SkipBurst => "skip_burst",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A process was asked to do something its bursts do not allow. Every variant
+/// names the process, the operation and the global clock at the time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimError {
+    /// The process has no bursts left.
+    NoBurst {
+        /// Name of the process.
+        process: String,
+        /// Operation that failed.
+        operation: Operation,
+        /// Global clock when the operation failed.
+        global_clock: i32,
+    },
+    /// The current burst is an IO burst where a CPU burst was expected.
+    NotCpuBurst {
+        /// Name of the process.
+        process: String,
+        /// Operation that failed.
+        operation: Operation,
+        /// Global clock when the operation failed.
+        global_clock: i32,
+    },
+    /// The current burst is a CPU burst where an IO burst was expected.
+    NotIoBurst {
+        /// Name of the process.
+        process: String,
+        /// Operation that failed.
+        operation: Operation,
+        /// Global clock when the operation failed.
+        global_clock: i32,
+    },
+    /// The process was handed a negative time-quanta.
+    NegativeQuanta {
+        /// Name of the process.
+        process: String,
+        /// Operation that failed.
+        operation: Operation,
+        /// Global clock when the operation failed.
+        global_clock: i32,
+        /// The time-quanta handed to the process.
+        time_quanta: i32,
+    },
+}
+
+impl SimError {
+    /// Name of the process the error is about.
+    pub fn process(&self) -> &str {
+        match self {
+            SimError::NoBurst { process, .. }
+            | SimError::NotCpuBurst { process, .. }
+            | SimError::NotIoBurst { process, .. }
+            | SimError::NegativeQuanta { process, .. } => process,
+        }
+    }
+
+    /// Operation that failed.
+    pub fn operation(&self) -> Operation {
+        match self {
+            SimError::NoBurst { operation, .. }
+            | SimError::NotCpuBurst { operation, .. }
+            | SimError::NotIoBurst { operation, .. }
+            | SimError::NegativeQuanta { operation, .. } => *operation,
+        }
+    }
+
+    /// Global clock when the operation failed.
+    pub fn global_clock(&self) -> i32 {
+        match self {
+            SimError::NoBurst { global_clock, .. }
+            | SimError::NotCpuBurst { global_clock, .. }
+            | SimError::NotIoBurst { global_clock, .. }
+            | SimError::NegativeQuanta { global_clock, .. } => *global_clock,
+        }
+    }
+}
+
+impl Display for SimError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "process \"{}\" failed to {} at global clock {}: ",
+            self.process(),
+            self.operation(),
+            self.global_clock()
+        )?;
+        match self {
+            SimError::NoBurst { .. } => write!(f, "no bursts left"),
+            SimError::NotCpuBurst { .. } => write!(f, "current burst is not a CPU burst"),
+            SimError::NotIoBurst { .. } => write!(f, "current burst is not an IO burst"),
+            SimError::NegativeQuanta { time_quanta, .. } => {
+                write!(f, "negative time-quanta {}", time_quanta)
+            }
+        }
+    }
+}
+
+impl Error for SimError {}
+
+/// Something wrong with one process of a workload, found before simulating it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The process has no bursts at all.
+    NoBursts,
+    /// The first burst is not a CPU burst.
+    StartsWithIo,
+    /// The last burst is not a CPU burst.
+    EndsWithIo,
+    /// The burst is of the same kind as the one before it.
+    NotAlternating,
+    /// The burst lasts zero or a negative number of time units.
+    NonPositiveBurst(i32),
+    /// The process arrives before time 0.
+    NegativeArrival(i32),
+    /// The process asks for a time-quanta of zero or a negative number of time units.
+    NonPositiveQuanta(i32),
+    /// Adding up the arrival time and the bursts up to this one overflows the clock.
+    Overflow,
+    /// Another process before this one has the same name.
+    DuplicateName,
+    /// The periodic task has a period of zero or a negative number of time units.
+    NonPositivePeriod(i32),
+    /// The periodic task has a relative deadline of zero or a negative number of
+    /// time units.
+    NonPositiveDeadline(i32),
+    /// The process has burst deadlines, but not one for every CPU burst.
+    DeadlineCount {
+        /// Number of CPU bursts of the process.
+        expected: usize,
+        /// Number of burst deadlines given.
+        found: usize,
+    },
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Problem::NoBursts => write!(f, "process has no bursts"),
+            Problem::StartsWithIo => write!(f, "first burst must be a CPU burst"),
+            Problem::EndsWithIo => write!(f, "last burst must be a CPU burst"),
+            Problem::NotAlternating => write!(f, "burst has the same kind as the one before it"),
+            Problem::NonPositiveBurst(duration) => {
+                write!(f, "burst of {} time units, must be positive", duration)
+            }
+            Problem::NegativeArrival(time) => {
+                write!(f, "arrival time {} is before time 0", time)
+            }
+            Problem::NonPositiveQuanta(quanta) => {
+                write!(f, "time-quanta of {} time units, must be positive", quanta)
+            }
+            Problem::Overflow => write!(f, "total time overflows the global clock"),
+            Problem::DuplicateName => write!(f, "name is used by another process"),
+            Problem::NonPositivePeriod(period) => {
+                write!(f, "period of {} time units, must be positive", period)
+            }
+            Problem::NonPositiveDeadline(deadline) => {
+                write!(
+                    f,
+                    "relative deadline of {} time units, must be positive",
+                    deadline
+                )
+            }
+            Problem::DeadlineCount { expected, found } => write!(
+                f,
+                "{} burst deadlines for {} CPU bursts, must be one per CPU burst",
+                found, expected
+            ),
+        }
+    }
+}
+
+/// A [`Problem`] with the process, and burst if any, it was found at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkloadError {
+    /// Name of the process.
+    pub process: String,
+    /// Index of the burst in the process, None for problems with the whole process.
+    pub burst: Option<usize>,
+    /// What is wrong.
+    pub problem: Problem,
+}
+
+impl Display for WorkloadError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.burst {
+            Some(index) => write!(
+                f,
+                "process \"{}\" burst {}: {}",
+                self.process, index, self.problem
+            ),
+            None => write!(f, "process \"{}\": {}", self.process, self.problem),
+        }
+    }
+}
+
+/// Every problem found in a workload, in the order of the processes and bursts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidWorkload {
+    /// The problems, never empty.
+    pub errors: Vec<WorkloadError>,
+}
+
+impl Display for InvalidWorkload {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "invalid workload, {} problem(s):", self.errors.len())?;
+        for error in self.errors.iter() {
+            write!(f, "\n  {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for InvalidWorkload {}
@@ -0,0 +1,112 @@
+use crate::process::Process;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// Things that can happen at a point in time during the simulation.
+#[derive(Debug)]
+pub enum EventKind {
+    /// A process enters the system and becomes ready.
+    Arrival(Box<Process>),
+    /// A process finishes its IO burst, the event's sequence number identifies it.
+    IoComplete,
+    /// The running process used its whole time-quanta before its CPU burst completed.
+    QuantumExpired,
+    /// The running process completed its CPU burst and moves on to its IO burst.
+    BurstComplete,
+    /// The running process completed its last CPU burst.
+    Exit,
+    /// The running process reached the deadline of its CPU burst before completing
+    /// it, and the scheduler drops late work.
+    Overrun,
+}
+
+#[derive(Debug)]
+pub struct Event {
+    pub time: i32,
+    /// Insertion order, breaks ties between events at the same time.
+    pub seq: u64,
+    pub kind: EventKind,
+}
+
+// Events are only ever compared by time, then insertion order.
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.time, self.seq).cmp(&(other.time, other.seq))
+    }
+}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Event {}
+
+/// Time-ordered queue of pending events, earliest first. Events scheduled for the
+/// same time come out in the order they were pushed.
+#[derive(Default)]
+pub struct EventQueue {
+    heap: BinaryHeap<Reverse<Event>>,
+    next_seq: u64,
+}
+
+impl EventQueue {
+    pub fn new() -> EventQueue {
+        Default::default()
+    }
+
+    /// Schedule `kind` to happen at `time`, returns the sequence number given
+    /// to the event.
+    pub fn push(&mut self, time: i32, kind: EventKind) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.heap.push(Reverse(Event { time, seq, kind }));
+        seq
+    }
+
+    pub fn pop(&mut self) -> Option<Event> {
+        self.heap.pop().map(|Reverse(event)| event)
+    }
+
+    pub fn peek(&self) -> Option<&Event> {
+        self.heap.peek().map(|Reverse(event)| event)
+    }
+
+    pub fn peek_time(&self) -> Option<i32> {
+        self.heap.peek().map(|Reverse(event)| event.time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pops_by_time_then_insertion() {
+        let mut events = EventQueue::new();
+        events.push(10, EventKind::QuantumExpired);
+        events.push(3, EventKind::IoComplete);
+        events.push(10, EventKind::Exit);
+        events.push(3, EventKind::BurstComplete);
+
+        assert_eq!(events.peek_time(), Some(3));
+        assert!(matches!(events.pop().unwrap().kind, EventKind::IoComplete));
+        assert!(matches!(
+            events.pop().unwrap().kind,
+            EventKind::BurstComplete
+        ));
+        assert!(matches!(
+            events.pop().unwrap().kind,
+            EventKind::QuantumExpired
+        ));
+        assert!(matches!(events.pop().unwrap().kind, EventKind::Exit));
+        assert!(events.pop().is_none());
+    }
+}
@@ -0,0 +1,53 @@
+//! Simulator for CPU process-scheduling algorithms.
+//!
+//! A workload is a queue of [`Process`]es, each a list of alternating CPU and IO
+//! bursts. A [`Scheduler`] decides which ready process gets the CPU and for how
+//! long, and [`simulate`] runs the workload to completion under it, returning a
+//! [`SimulationResult`] with the response, waiting and turnaround time of every
+//! process.
+//!
+//! ```
+//! use process_scheduler_simulator::{simulate, workload, Fcfs, Policy};
+//!
+//! // Pick a built-in policy by menu entry...
+//! let result = Policy::Mlfq.run(workload::default_workload())?;
+//! assert_eq!(result.global_clock, 621);
+//!
+//! // ...or drive any `Scheduler` directly.
+//! let result = simulate(&mut Fcfs::new(), workload::default_workload())?;
+//! println!("{}", result);
+//! # Ok::<(), process_scheduler_simulator::SimError>(())
+//! ```
+//!
+//! A malformed workload makes the simulation return a [`SimError`] naming the
+//! process, the operation and the global clock, rather than panic.
+
+#![warn(missing_docs)]
+
+pub mod burst;
+pub mod engine;
+pub mod error;
+mod event_queue;
+pub mod observer;
+pub mod periodic;
+pub mod process;
+pub mod result;
+mod rng;
+pub mod scheduler;
+pub mod workload;
+
+pub use burst::{Burst, Bursts};
+pub use engine::{simulate, simulate_with, SimulationConfig};
+pub use error::{InvalidWorkload, SimError};
+pub use observer::{ConsoleObserver, EventLog, Observer, SimEvent, SimView};
+pub use periodic::PeriodicTask;
+pub use process::{DeadlineOutcome, Process, SchedulingParams};
+pub use result::{
+    CpuMetrics, CpuRun, DeadlineMetrics, LoadSample, ProcessMetrics, ShareMetrics, ShareSample,
+    SimulationResult,
+};
+pub use scheduler::{
+    Assignment, Cfs, Context, Edf, Eevdf, Fcfs, FixedPriority, Hrrn, Level, LevelPolicy, Llf,
+    Lottery, Migration, Mlfq, MlfqConfig, Overrun, PerCpu, Policy, Priority, RoundRobin, Scheduler,
+    Sjf, Srtf, Stride,
+};
@@ -1,176 +1,23 @@
-mod process;
-mod scheduler;
-
+use process_scheduler_simulator::{
+    periodic, simulate_with, workload, Assignment, ConsoleObserver, Edf, Llf, Overrun, PerCpu,
+    PeriodicTask, Policy, Process, RoundRobin, Scheduler, SimulationConfig,
+};
 use std::collections::VecDeque;
 use std::io;
 
 fn main() {
-
-    // Setting up processes for simulation.
-
-    let process = process::Process {
-        process_bursts: VecDeque::from(vec![5, 27, 3, 31, 5, 43, 4, 18, 6, 22, 4, 26, 3, 24, 5]),
-        total_process_time: 5 + 27 + 3 + 31 + 5 + 43 + 4 + 18 + 6 + 22 + 4 + 26 + 3 + 24 + 5,
-        name: "P1".to_string(),
-        ..Default::default()
-    };
-
-    let process_2 = process::Process {
-        process_bursts: VecDeque::from(vec![
-            4, 48, 5, 44, 7, 42, 12, 37, 9, 76, 4, 41, 9, 31, 7, 43, 8,
-        ]),
-        total_process_time: 4
-            + 48
-            + 5
-            + 44
-            + 7
-            + 42
-            + 12
-            + 37
-            + 9
-            + 76
-            + 4
-            + 41
-            + 9
-            + 31
-            + 7
-            + 43
-            + 8,
-        name: "P2".to_string(),
-        ..Default::default()
-    };
-
-    let process_3 = process::Process {
-        process_bursts: VecDeque::from(vec![
-            8, 33, 12, 41, 18, 65, 14, 21, 4, 61, 15, 18, 14, 26, 5, 31, 6,
-        ]),
-        total_process_time: 8
-            + 33
-            + 12
-            + 41
-            + 18
-            + 65
-            + 14
-            + 21
-            + 4
-            + 61
-            + 15
-            + 18
-            + 14
-            + 26
-            + 5
-            + 31
-            + 6,
-        name: "P3".to_string(),
-        ..Default::default()
-    };
-
-    let process_4 = process::Process {
-        process_bursts: VecDeque::from(vec![
-            3, 35, 4, 41, 5, 45, 3, 51, 4, 61, 5, 54, 6, 82, 5, 77, 3,
-        ]),
-        total_process_time: 3
-            + 35
-            + 4
-            + 41
-            + 5
-            + 45
-            + 3
-            + 51
-            + 4
-            + 61
-            + 5
-            + 54
-            + 6
-            + 82
-            + 5
-            + 77
-            + 3,
-        name: "P4".to_string(),
-        ..Default::default()
-    };
-
-    let process_5 = process::Process {
-        process_bursts: VecDeque::from(vec![
-            16, 24, 17, 21, 5, 36, 16, 26, 7, 31, 13, 28, 11, 21, 6, 13, 3, 11, 4,
-        ]),
-        total_process_time: 16
-            + 24
-            + 17
-            + 21
-            + 5
-            + 36
-            + 16
-            + 26
-            + 7
-            + 31
-            + 13
-            + 28
-            + 11
-            + 21
-            + 6
-            + 13
-            + 3
-            + 11
-            + 4,
-        name: "P5".to_string(),
-        ..Default::default()
-    };
-
-    let process_6 = process::Process {
-        process_bursts: VecDeque::from(vec![
-            11, 22, 4, 8, 5, 10, 6, 12, 7, 14, 9, 18, 12, 24, 15, 30, 8,
-        ]),
-        total_process_time: 11
-            + 22
-            + 4
-            + 8
-            + 5
-            + 10
-            + 6
-            + 12
-            + 7
-            + 14
-            + 9
-            + 18
-            + 12
-            + 24
-            + 15
-            + 30
-            + 8,
-        name: "P6".to_string(),
-        ..Default::default()
-    };
-
-    let process_7 = process::Process {
-        process_bursts: VecDeque::from(vec![
-            14, 46, 17, 41, 11, 42, 15, 21, 4, 32, 7, 19, 16, 33, 10,
-        ]),
-        total_process_time: 14 + 46 + 17 + 41 + 11 + 42 + 15 + 21 + 4 + 32 + 7 + 19 + 16 + 33 + 10,
-        name: "P7".to_string(),
-        ..Default::default()
-    };
-
-    let process_8 = process::Process {
-        process_bursts: VecDeque::from(vec![4, 14, 5, 33, 6, 51, 14, 73, 16, 87, 6]),
-        total_process_time: 4 + 14 + 5 + 33 + 6 + 51 + 14 + 73 + 16 + 87 + 6,
-        name: "P8".to_string(),
-        ..Default::default()
-    };
-
-    let processes = VecDeque::from(vec![
-        process, process_2, process_3, process_4, process_5, process_6, process_7, process_8,
-    ]);
-
     // Starting CMD Portion
     println!("PROCESS SCHEDULING SIMULATOR\n");
 
     println!("Main Menu:");
-    println!("  1. First Come First Serve");
-    println!("  2. Shortest Job First");
-    println!("  3. Multi-Level Feedback Queue");
+    for (number, policy) in Policy::ALL.iter().enumerate() {
+        println!("  {}. {}", number + 1, policy.label());
+    }
 
-    println!("Please select the queue that you would like to simulate today, (1, 2, 3)\n");
+    println!(
+        "Please select the queue that you would like to simulate today, (1-{})\n",
+        Policy::ALL.len()
+    );
 
     // Read chars from STDIN
     let mut selection = String::new();
@@ -179,15 +26,263 @@ fn main() {
         .expect("Failed to read line");
 
     // Convert from chars to numbers if chars are representations of numbers and nothing else.
-    let selection: i32 = selection.trim().parse().expect("Please type a number!");
+    let selection: usize = selection.trim().parse().expect("Please type a number!");
 
     // Run scheduler based on selection
-    match selection {
-        1 => scheduler::fcfs_scheduler(processes),
-        2 => scheduler::sjf_scheduler(processes),
-        3 => scheduler::mlfq_scheduler(processes),
-        _ => println!("I'm sorry, that guess doesn't match an option"),
+    match selection
+        .checked_sub(1)
+        .and_then(|index| Policy::ALL.get(index))
+    {
+        Some(policy) => {
+            // Policy parameters are asked for once, every CPU gets a scheduler alike.
+            let policy = *policy;
+            let time_quanta = match policy {
+                Policy::RoundRobin => read_time_quanta(),
+                _ => RoundRobin::DEFAULT_QUANTA,
+            };
+            let overrun = match policy {
+                Policy::Edf => read_overrun(),
+                _ => Overrun::default(),
+            };
+            let threshold = match policy {
+                Policy::Llf => read_threshold(),
+                _ => 0,
+            };
+            let make_scheduler = move || -> Box<dyn Scheduler> {
+                match policy {
+                    Policy::RoundRobin => Box::new(RoundRobin::new(time_quanta)),
+                    Policy::Edf => Box::new(Edf::new().with_overrun(overrun)),
+                    Policy::Llf => Box::new(Llf::new().with_threshold(threshold)),
+                    _ => policy.scheduler(),
+                }
+            };
+
+            // Setting up processes for simulation.
+            let (processes, tasks) = read_workload();
+            let mut config = SimulationConfig {
+                cpus: read_cpus(),
+                dispatch_latency: read_cost("dispatch latency"),
+                process_switch_cost: read_cost("process switch cost"),
+                redispatch_cost: read_cost("redispatch cost"),
+                ..Default::default()
+            };
+            let mut scheduler: Box<dyn Scheduler> = if config.cpus > 1 && read_per_cpu() {
+                config.migration_cost = read_cost("migration cost");
+                Box::new(PerCpu::new(config.cpus, make_scheduler))
+            } else {
+                make_scheduler()
+            };
+
+            // Periodic tasks under fixed priorities are analyzed before simulating.
+            let assignment = match policy {
+                Policy::RateMonotonic => Some(Assignment::RateMonotonic),
+                Policy::DeadlineMonotonic => Some(Assignment::DeadlineMonotonic),
+                _ => None,
+            };
+            let analysis = tasks.zip(assignment).map(|(tasks, assignment)| {
+                periodic::analyze(&tasks, assignment).expect("built-in task set is valid")
+            });
+            if let Some(analysis) = analysis.as_ref() {
+                println!("{}\n", analysis);
+            }
+
+            match simulate_with(
+                scheduler.as_mut(),
+                processes,
+                &config,
+                &mut [&mut ConsoleObserver],
+            ) {
+                // Print Final Results
+                Ok(result) => {
+                    println!("\n{}", result);
+                    if let Some(analysis) = analysis.as_ref() {
+                        println!("\n{}", periodic::compare(analysis, &result));
+                    }
+                }
+                Err(error) => eprintln!("Simulation failed: {}", error),
+            }
+        }
+        None => println!("I'm sorry, that guess doesn't match an option"),
     }
 
     println!("Goodbye!")
 }
+
+fn read_workload() -> (VecDeque<Process>, Option<Vec<PeriodicTask>>) {
+    /*
+        Ask for the workload to simulate, an empty line keeps the default one. Along
+        with the periodic tasks that release it, if any.
+    */
+    println!("Please select the workload, (blank for 1)");
+    println!("  1. Default");
+    println!("  2. MLFQ gaming");
+    println!("  3. Deadlines");
+    println!("  4. Periodic tasks\n");
+
+    let mut selection = String::new();
+    io::stdin()
+        .read_line(&mut selection)
+        .expect("Failed to read line");
+
+    match selection.trim() {
+        "2" => (workload::gaming_workload(), None),
+        "3" => (workload::deadline_workload(), None),
+        "4" => (
+            workload::periodic_workload(),
+            Some(workload::periodic_task_set()),
+        ),
+        "" | "1" => (workload::default_workload(), None),
+        _ => {
+            println!("I'm sorry, that guess doesn't match an option, using the default");
+            (workload::default_workload(), None)
+        }
+    }
+}
+
+fn read_cpus() -> usize {
+    /*
+        Ask for the number of CPUs sharing the ready queue(s), an empty line keeps
+        a single one.
+    */
+    println!("Please enter the number of CPUs, (blank for 1)\n");
+
+    let mut cpus = String::new();
+    io::stdin()
+        .read_line(&mut cpus)
+        .expect("Failed to read line");
+
+    match cpus.trim() {
+        "" => 1,
+        cpus => match cpus.parse() {
+            Ok(cpus) if cpus > 0 => cpus,
+            _ => {
+                println!("Number of CPUs must be a positive number, using 1");
+                1
+            }
+        },
+    }
+}
+
+fn read_per_cpu() -> bool {
+    /*
+        Ask whether every CPU has run queue(s) of its own, an empty line keeps them
+        shared.
+    */
+    println!("Please select the run queues, (blank for 1)");
+    println!("  1. Global");
+    println!("  2. Per-CPU, with load balancing and work stealing\n");
+
+    let mut selection = String::new();
+    io::stdin()
+        .read_line(&mut selection)
+        .expect("Failed to read line");
+
+    match selection.trim() {
+        "" | "1" => false,
+        "2" => true,
+        _ => {
+            println!("I'm sorry, that guess doesn't match an option, using a global one");
+            false
+        }
+    }
+}
+
+fn read_cost(cost: &str) -> i32 {
+    /*
+        Ask for a time charged by the machine, such as the time a process loses
+        moving to another CPU, an empty line makes it free.
+    */
+    println!("Please enter the {}, (blank for 0)\n", cost);
+
+    let mut time = String::new();
+    io::stdin()
+        .read_line(&mut time)
+        .expect("Failed to read line");
+
+    match time.trim() {
+        "" => 0,
+        time => match time.parse() {
+            Ok(time) if time >= 0 => time,
+            _ => {
+                println!("The {} must not be a negative number, using 0", cost);
+                0
+            }
+        },
+    }
+}
+
+fn read_overrun() -> Overrun {
+    /*
+        Ask what happens to CPU bursts past their deadline, an empty line lets
+        them continue.
+    */
+    println!("Please select what happens to late CPU bursts, (blank for 1)");
+    println!("  1. Continue");
+    println!("  2. Abort the job");
+    println!("  3. Skip the burst\n");
+
+    let mut selection = String::new();
+    io::stdin()
+        .read_line(&mut selection)
+        .expect("Failed to read line");
+
+    match selection.trim() {
+        "" | "1" => Overrun::Continue,
+        "2" => Overrun::AbortJob,
+        "3" => Overrun::SkipBurst,
+        _ => {
+            println!("I'm sorry, that guess doesn't match an option, letting them continue");
+            Overrun::Continue
+        }
+    }
+}
+
+fn read_time_quanta() -> i32 {
+    /*
+        Ask for the Round-Robin time-quanta, an empty line keeps the default.
+    */
+    println!(
+        "Please enter the time-quanta, (blank for {})\n",
+        RoundRobin::DEFAULT_QUANTA
+    );
+
+    let mut time_quanta = String::new();
+    io::stdin()
+        .read_line(&mut time_quanta)
+        .expect("Failed to read line");
+
+    match time_quanta.trim() {
+        "" => RoundRobin::DEFAULT_QUANTA,
+        time_quanta => match time_quanta.parse() {
+            Ok(time_quanta) if time_quanta > 0 => time_quanta,
+            _ => {
+                println!("Time-quanta must be a positive number, using the default");
+                RoundRobin::DEFAULT_QUANTA
+            }
+        },
+    }
+}
+
+fn read_threshold() -> i32 {
+    /*
+        Ask for the Least Laxity First tie-break threshold, an empty line switches
+        as soon as laxities cross.
+    */
+    println!("Please enter the tie-break threshold, (blank for 0)\n");
+
+    let mut threshold = String::new();
+    io::stdin()
+        .read_line(&mut threshold)
+        .expect("Failed to read line");
+
+    match threshold.trim() {
+        "" => 0,
+        threshold => match threshold.parse() {
+            Ok(threshold) if threshold >= 0 => threshold,
+            _ => {
+                println!("Threshold must not be a negative number, using 0");
+                0
+            }
+        },
+    }
+}
@@ -0,0 +1,339 @@
+//! Typed events of a simulation, delivered to user-supplied observers.
+
+use crate::process::Process;
+use crate::scheduler::Scheduler;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+/// Something that happened during a simulation. Every event carries the global
+/// clock it happened at and the name of the process it is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimEvent {
+    /// The process entered the system and joins the ready queue.
+    Arrival {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+    },
+    /// The scheduler put the process on a CPU. It starts running once the
+    /// context switch is paid for.
+    Dispatch {
+        /// Global clock of the event.
+        time: i32,
+        /// CPU the process runs on, from 0.
+        cpu: usize,
+        /// Name of the process.
+        process: String,
+        /// Time-quanta the process may use.
+        time_quanta: i32,
+        /// Context switch overhead, migration included, charged before the process
+        /// runs.
+        switch_cost: i32,
+    },
+    /// The running process was taken off the CPU before its time-quanta ran out.
+    Preempt {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+        /// Name of the process that became ready and took over the CPU.
+        by: String,
+    },
+    /// The running process used up its time-quanta before its CPU burst completed.
+    QuantumExpired {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+    },
+    /// The process completed a CPU burst and started an IO burst.
+    IoStart {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+        /// IO device the burst is on.
+        device: usize,
+        /// Global clock the IO burst completes at.
+        return_time: i32,
+    },
+    /// The process completed its IO burst, it joins the ready queue at the next
+    /// scheduling decision.
+    IoComplete {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+    },
+    /// The scheduler moved the process to a lower priority level.
+    Demote {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+        /// Level the process was at, 0 being the highest priority.
+        from: usize,
+        /// Level the process is moved to.
+        to: usize,
+    },
+    /// The scheduler moved the process to a higher priority level.
+    Promote {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+        /// Level the process was at, 0 being the highest priority.
+        from: usize,
+        /// Level the process is moved to.
+        to: usize,
+    },
+    /// The process is dispatched on another CPU than the one it last ran on, e.g.
+    /// after load balancing moved it. Sent right before the dispatch, which pays
+    /// the migration cost.
+    Migrate {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+        /// CPU the process last ran on, from 0.
+        from: usize,
+        /// CPU the process is dispatched on.
+        to: usize,
+    },
+    /// The process did not complete its CPU burst, or job, by its deadline. Sent
+    /// when the work completes late, or when it is dropped.
+    DeadlineMiss {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+        /// Deadline that was missed.
+        deadline: i32,
+    },
+    /// The process completed its last CPU burst.
+    Exit {
+        /// Global clock of the event.
+        time: i32,
+        /// Name of the process.
+        process: String,
+    },
+    /// Nothing is ready to run, the CPU sits idle until the next event.
+    Idle {
+        /// Global clock the CPU goes idle at.
+        time: i32,
+        /// CPU that goes idle, from 0.
+        cpu: usize,
+        /// Global clock of the next event.
+        until: i32,
+    },
+}
+
+impl SimEvent {
+    /// Global clock the event happened at.
+    pub fn time(&self) -> i32 {
+        match self {
+            SimEvent::Arrival { time, .. }
+            | SimEvent::Dispatch { time, .. }
+            | SimEvent::Preempt { time, .. }
+            | SimEvent::QuantumExpired { time, .. }
+            | SimEvent::IoStart { time, .. }
+            | SimEvent::IoComplete { time, .. }
+            | SimEvent::Demote { time, .. }
+            | SimEvent::Promote { time, .. }
+            | SimEvent::Migrate { time, .. }
+            | SimEvent::DeadlineMiss { time, .. }
+            | SimEvent::Exit { time, .. }
+            | SimEvent::Idle { time, .. } => *time,
+        }
+    }
+}
+
+// One line per event for console output.
+impl Display for SimEvent {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SimEvent::Arrival { time, process } => {
+                write!(f, "Process {} arrives at {} units", process, time)
+            }
+            SimEvent::Dispatch {
+                time,
+                cpu,
+                process,
+                time_quanta,
+                switch_cost,
+            } => write!(
+                f,
+                "Process {} is dispatched on CPU {} at {} units for {} units after a switch of {}",
+                process, cpu, time, time_quanta, switch_cost
+            ),
+            SimEvent::Preempt { time, process, by } => write!(
+                f,
+                "Process {} is preempted by {} at {} units",
+                process, by, time
+            ),
+            SimEvent::QuantumExpired { time, process } => write!(
+                f,
+                "Process {} used up its time-quanta at {} units",
+                process, time
+            ),
+            SimEvent::IoStart {
+                time,
+                process,
+                device,
+                return_time,
+            } => write!(
+                f,
+                "Process {} starts IO on device {} at {} units, back at {}",
+                process, device, time, return_time
+            ),
+            SimEvent::IoComplete { time, process } => {
+                write!(f, "Process {} is back from IO at {} units", process, time)
+            }
+            SimEvent::Demote {
+                time,
+                process,
+                from,
+                to,
+            } => write!(
+                f,
+                "Process {} is demoted from level {} to level {} at {} units",
+                process, from, to, time
+            ),
+            SimEvent::Promote {
+                time,
+                process,
+                from,
+                to,
+            } => write!(
+                f,
+                "Process {} is promoted from level {} to level {} at {} units",
+                process, from, to, time
+            ),
+            SimEvent::Migrate {
+                time,
+                process,
+                from,
+                to,
+            } => write!(
+                f,
+                "Process {} migrates from CPU {} to CPU {} at {} units",
+                process, from, to, time
+            ),
+            SimEvent::DeadlineMiss {
+                time,
+                process,
+                deadline,
+            } => write!(
+                f,
+                "Process {} missed its deadline of {} units at {} units",
+                process, deadline, time
+            ),
+            SimEvent::Exit { time, process } => {
+                write!(f, "Process {} is done at {} units!", process, time)
+            }
+            SimEvent::Idle { time, cpu, until } => {
+                write!(f, "CPU {} is idle from {} until {} units", cpu, time, until)
+            }
+        }
+    }
+}
+
+/// State of the simulation at the time an event is delivered.
+pub struct SimView<'a> {
+    pub(crate) global_clock: i32,
+    pub(crate) scheduler: &'a dyn Scheduler,
+    pub(crate) io_queue: &'a BTreeMap<u64, Process>,
+    // Process on every CPU, in CPU order.
+    pub(crate) running: Vec<Option<&'a Process>>,
+}
+
+impl<'a> SimView<'a> {
+    /// Current value of the global clock.
+    pub fn global_clock(&self) -> i32 {
+        self.global_clock
+    }
+
+    /// Named ready queues of the scheduler.
+    pub fn ready_queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        self.scheduler.queues()
+    }
+
+    /// Processes currently waiting on IO, in the order they started their IO burst.
+    pub fn io_queue(&self) -> impl Iterator<Item = &Process> {
+        self.io_queue.values()
+    }
+
+    /// Processes on a CPU, in CPU order.
+    pub fn running(&self) -> impl Iterator<Item = &Process> {
+        self.running.iter().flatten().copied()
+    }
+
+    /// Process on CPU `cpu`, if any.
+    pub fn running_on(&self, cpu: usize) -> Option<&Process> {
+        self.running.get(cpu).copied().flatten()
+    }
+
+    /// Number of CPUs of the simulated machine.
+    pub fn cpus(&self) -> usize {
+        self.running.len()
+    }
+}
+
+/// Receives every event of a simulation, in the order they happen.
+pub trait Observer {
+    /// Called once per event, with the state of the simulation right after it.
+    fn on_event(&mut self, event: &SimEvent, view: &SimView);
+}
+
+/// Prints every event to the console, along with the ready and IO queues on
+/// every dispatch.
+#[derive(Debug, Default)]
+pub struct ConsoleObserver;
+
+impl Observer for ConsoleObserver {
+    fn on_event(&mut self, event: &SimEvent, view: &SimView) {
+        if let SimEvent::Dispatch { .. } = event {
+            // Print Context Switch Information.
+            println!(
+                "Global Clock is {} ---------------------------",
+                view.global_clock()
+            );
+            if view.cpus() > 1 {
+                for cpu in 0..view.cpus() {
+                    println!("Running Process on CPU {}:", cpu);
+                    print_queue(view.running_on(cpu));
+                }
+            } else {
+                println!("Running Process:");
+                print_queue(view.running());
+            }
+            for (label, queue) in view.ready_queues() {
+                println!("Current {} Queue:", label);
+                print_queue(queue);
+            }
+            println!("Current IO Queue:");
+            print_queue(view.io_queue());
+        }
+        println!("{}", event);
+    }
+}
+
+/// Keeps every event, e.g. to export or inspect them after the simulation.
+#[derive(Debug, Default)]
+pub struct EventLog {
+    /// Events in the order they happened.
+    pub events: Vec<SimEvent>,
+}
+
+impl Observer for EventLog {
+    fn on_event(&mut self, event: &SimEvent, _view: &SimView) {
+        self.events.push(event.clone());
+    }
+}
+
+fn print_queue<'a>(process_queue: impl IntoIterator<Item = &'a Process>) {
+    for process in process_queue {
+        println!("{}", process);
+    }
+}
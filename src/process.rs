@@ -1,136 +1,393 @@
+//! Processes and their CPU and IO bursts.
+
+use crate::burst::{Burst, Bursts};
+use crate::error::{Operation, SimError};
 use std::cmp::{min, Ordering};
-use std::fmt::{Display, Formatter, Result};
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
 
-#[derive(Default, Debug)]
+/// A process of the simulated workload, together with the bookkeeping the
+/// schedulers and engine keep about it.
+#[derive(Debug, Clone)]
 pub struct Process {
-    // Vector queue containing all processing bursts (CPU and I/O)
-    // When number of processes is odd, it is currently CPU burst
-    // When number of processes is even, it is currently I/O burst
-    pub process_bursts: std::collections::VecDeque<i32>,
-    // Records first time that Process was activated.
-    // Used to calculate Response Time.
+    // All processing bursts (CPU and I/O), the current one at the front.
+    process_bursts: Bursts,
+    /// Records first time that Process was activated.
+    /// Used to calculate Response Time.
     pub first_accessed: Option<i32>,
-    // Last time that this process had run according to global clock
-    // The final value is last_accessed is also the turnaround time, and can be used to calculate the waiting time.
+    /// Last clock unit this process ran in, according to the global clock.
+    /// Once it completes, the turnaround time is `last_accessed - arrival_time`,
+    /// which in turn gives the waiting time.
     pub last_accessed: i32,
-    // ID String for process
+    /// ID String for process
     pub name: String,
-    // Time Point calculated against global clock for processes return from
-    // IO burst state.
+    /// Time Point against global clock at which the process enters the ready queue.
+    /// Response, waiting and turnaround times are measured from it.
+    pub arrival_time: i32,
+    /// Time Point calculated against global clock for processes return from
+    /// IO burst state.
     pub return_from_io_time: i32,
-    // Calculated at process creation time, total burst times summed up from process_bursts
-    // used to calculate waiting time later.
+    /// Calculated at process creation time, total burst times summed up from process_bursts
+    /// used to calculate waiting time later.
     pub total_process_time: i32,
-    // Determines whether the current burst has completed.
+    /// Determines whether the current burst has completed.
     pub burst_completed: bool,
+    /// What the process asks of the schedulers that look at more than its bursts.
+    pub params: SchedulingParams,
+    /// Time Point against global clock at which the process last entered the ready
+    /// queue, None while it is running or in IO.
+    pub ready_since: Option<i32>,
+    /// Longest time the process spent in the ready queue in one go, a measure of
+    /// starvation.
+    pub longest_wait: i32,
+    /// Every deadline of the process met or missed so far.
+    pub deadline_outcomes: Vec<DeadlineOutcome>,
+    /// CPU the process last ran on, None until it first gets on a CPU.
+    pub last_cpu: Option<usize>,
+    /// Number of times the process got on a CPU other than the one it last ran on.
+    pub migrations: usize,
+}
+
+/// Parameters of a process for the schedulers that use them, e.g. its priority or
+/// deadline. Every scheduler ignores those of other policies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchedulingParams {
+    /// Time-quanta this process asks for, used instead of the scheduler's own by
+    /// schedulers that allow it, e.g. Round-Robin.
+    pub time_quanta: Option<i32>,
+    /// Priority of the process for priority schedulers, lower numbers run first.
+    pub priority: i32,
+    /// Lottery tickets of the process, in the currency of its group if it has one,
+    /// [`Process::DEFAULT_TICKETS`] unless set otherwise.
+    pub tickets: u32,
+    /// Group the process belongs to, e.g. the user that started it.
+    pub group: Option<String>,
+    /// Nice value of the process for the Completely Fair Scheduler, from -20
+    /// (the largest share of the CPU) to 19 (the smallest).
+    pub nice: i32,
+    /// Time Point against global clock the whole job is due by, i.e. its last CPU
+    /// burst.
+    pub deadline: Option<i32>,
+    /// Time Points against global clock the CPU bursts left are due by, the current
+    /// one at the front. Bursts without one fall back to the job's deadline.
+    pub burst_deadlines: VecDeque<i32>,
+    /// Periodic task that released this process as one of its jobs, see
+    /// [`periodic::release_jobs`](crate::periodic::release_jobs).
+    pub task: Option<String>,
+    /// Period of the task that released this process, used by Rate Monotonic.
+    pub period: Option<i32>,
+}
+
+impl Default for SchedulingParams {
+    fn default() -> SchedulingParams {
+        SchedulingParams {
+            time_quanta: None,
+            priority: 0,
+            tickets: Process::DEFAULT_TICKETS,
+            group: None,
+            nice: 0,
+            deadline: None,
+            burst_deadlines: VecDeque::new(),
+            task: None,
+            period: None,
+        }
+    }
+}
+
+/// What became of a deadline of a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineOutcome {
+    /// Time Point against global clock the work was due by.
+    pub deadline: i32,
+    /// Time Point against global clock the work completed at, or was dropped at.
+    pub completion: i32,
+    /// Whether the work was dropped once it overran its deadline, rather than
+    /// completed.
+    pub dropped: bool,
+}
+
+impl DeadlineOutcome {
+    /// Whether the work was not done by its deadline.
+    pub fn missed(&self) -> bool {
+        self.dropped || self.completion > self.deadline
+    }
+
+    /// Completion less the deadline, negative when the work was done early.
+    pub fn lateness(&self) -> i32 {
+        self.completion - self.deadline
+    }
+
+    /// Lateness, or 0 when the work was done in time.
+    pub fn tardiness(&self) -> i32 {
+        self.lateness().max(0)
+    }
 }
 
 impl Process {
-    pub fn run(&mut self, time_quanta: i32, global_clock: i32) -> i32 {
-        /* Counts down on the current process burst with the time-quanta that the process was alloted.
-            Returns any un-used time-quanta. Updates total waiting time and time-last accessed.
-            Time quanta must be a positive integer.
-        */
+    /// Tickets of a process unless set otherwise.
+    pub const DEFAULT_TICKETS: u32 = 100;
+
+    /// New process named `name` that goes through `bursts`, arriving at time 0
+    /// with the default tickets. The total process time is derived from the
+    /// bursts, saturating rather than overflowing; see
+    /// [`workload::validate`](crate::workload::validate).
+    pub fn new(name: &str, bursts: Bursts) -> Process {
+        let total_process_time = bursts
+            .iter()
+            .fold(0i32, |total, burst| total.saturating_add(burst.duration()));
+
+        Process {
+            process_bursts: bursts,
+            first_accessed: None,
+            last_accessed: 0,
+            name: name.to_string(),
+            arrival_time: 0,
+            return_from_io_time: 0,
+            total_process_time,
+            burst_completed: false,
+            params: SchedulingParams::default(),
+            ready_since: None,
+            longest_wait: 0,
+            deadline_outcomes: Vec::new(),
+            last_cpu: None,
+            migrations: 0,
+        }
+    }
 
-        // Precondition, time_quanta cannot be a negative number.
-        assert!(time_quanta >= 0);
+    /// Same process arriving at `arrival_time`.
+    pub fn with_arrival_time(mut self, arrival_time: i32) -> Process {
+        self.arrival_time = arrival_time;
+        self
+    }
 
-        if let None = self.first_accessed {
-            self.first_accessed = Some(global_clock);
+    /// Same process with the scheduling parameters `params`.
+    pub fn with_params(mut self, params: SchedulingParams) -> Process {
+        self.params = params;
+        self
+    }
+
+    /// Counts down on the current process burst with the time-quanta that the process was alloted.
+    /// Returns any un-used time-quanta. Updates total waiting time and time-last accessed.
+    /// Time quanta must be a positive integer.
+    pub fn run(&mut self, time_quanta: i32, global_clock: i32) -> Result<i32, SimError> {
+        // Precondition, time_quanta cannot be a negative number.
+        if time_quanta < 0 {
+            return Err(SimError::NegativeQuanta {
+                process: self.name.clone(),
+                operation: Operation::Run,
+                global_clock,
+                time_quanta,
+            });
         }
 
-        // Get current process_burst from VeqDeque
-        let process_burst = match self.process_bursts.get_mut(0) {
-            Some(x) => x,
-            None => panic!("No burst found for Process"),
+        // Precondition, current burst must be a CPU burst.
+        let process_burst = match self.process_bursts.front_mut() {
+            Some(Burst::Cpu(remaining)) => remaining,
+            _ => return Err(self.burst_error(Operation::Run, global_clock)),
         };
 
         // Subtract time_quanta from current process burst to get unused time.
         // Unused time is greater than zero when current process burst becomes negative.
-        *process_burst = *process_burst - time_quanta;
+        *process_burst -= time_quanta;
         let unused_time: i32 = min(*process_burst, 0);
 
         // Set complete burst flag
         self.burst_completed = *process_burst <= 0;
 
-        // Update time last accessed as last bit of clock before process burst expires
-        self.last_accessed = global_clock + (time_quanta - unused_time) - 1;
+        if self.first_accessed.is_none() {
+            self.first_accessed = Some(global_clock);
+        }
+
+        // Update time last accessed as last bit of clock before process burst expires,
+        // unused time is negative here.
+        self.last_accessed = global_clock + (time_quanta + unused_time) - 1;
 
-        return i32::abs(unused_time);
+        Ok(i32::abs(unused_time))
     }
 
-    pub fn calc_return_time(&mut self, global_clock: i32) {
-        /*
-            Calculate return time of process from IO queue against global clock.
-            Function does not know if burst at top of queue is CPU or IO so the dev
-            must know whether or not that is true before hand.
-         */
-
-        // Get current CPU burst from top of Queue
-        let process_burst = match self.process_bursts.get(0) {
-            Some(num_reference) => num_reference,
-            None => panic!("No bursts found for Process"),
+    /// Take the process off the CPU at `global_clock`, `unused_time` units before
+    /// the end of the time-quanta it was last run with. The unused time goes back
+    /// on the current CPU burst.
+    pub fn preempt(&mut self, unused_time: i32, global_clock: i32) -> Result<(), SimError> {
+        // Precondition, current burst must be the CPU burst that was being run.
+        let process_burst = match self.process_bursts.front_mut() {
+            Some(Burst::Cpu(remaining)) => remaining,
+            _ => return Err(self.burst_error(Operation::Preempt, global_clock)),
+        };
+
+        // A burst that would have completed ran past zero by its left over time.
+        *process_burst = i32::max(*process_burst, 0) + unused_time;
+        self.burst_completed = *process_burst <= 0;
+
+        // Last bit of clock the process actually ran.
+        self.last_accessed = global_clock - 1;
+        Ok(())
+    }
+
+    /// Bursts left to go through, the current one at the front.
+    pub fn bursts(&self) -> &Bursts {
+        &self.process_bursts
+    }
+
+    /// Time units required to run the current CPU burst to 0, None if the
+    /// process is not at a CPU burst.
+    pub fn current_burst(&self) -> Option<i32> {
+        match self.process_bursts.front() {
+            Some(Burst::Cpu(remaining)) => Some(*remaining),
+            _ => None,
+        }
+    }
+
+    /// Time the whole job is due by after its arrival, used by Deadline Monotonic.
+    pub fn relative_deadline(&self) -> Option<i32> {
+        self.params
+            .deadline
+            .map(|deadline| deadline - self.arrival_time)
+    }
+
+    /// Time Point against global clock the current CPU burst is due by, if any.
+    pub fn current_deadline(&self) -> Option<i32> {
+        self.params
+            .burst_deadlines
+            .front()
+            .copied()
+            .or(self.params.deadline)
+    }
+
+    /// Drop the rest of the current CPU burst, which then counts as completed.
+    pub fn skip_burst(&mut self, global_clock: i32) -> Result<(), SimError> {
+        // Precondition, current burst must be a CPU burst.
+        let process_burst = match self.process_bursts.front_mut() {
+            Some(Burst::Cpu(remaining)) => remaining,
+            _ => return Err(self.burst_error(Operation::SkipBurst, global_clock)),
+        };
+
+        // The dropped time is no longer part of the process.
+        self.total_process_time -= i32::max(*process_burst, 0);
+        *process_burst = 0;
+        self.burst_completed = true;
+        Ok(())
+    }
+
+    /// Drop every burst left, the process leaves the system at `global_clock`.
+    pub fn abort(&mut self, global_clock: i32) {
+        while let Some(burst) = self.process_bursts.pop_front() {
+            self.total_process_time -= i32::max(burst.duration(), 0);
+        }
+        self.params.burst_deadlines.clear();
+        self.burst_completed = true;
+        self.last_accessed = i32::max(self.last_accessed, global_clock - 1);
+    }
+
+    /// True when the burst after the current one is an IO burst, i.e. the
+    /// process is not on its last CPU burst.
+    pub fn has_io_next(&self) -> bool {
+        matches!(self.process_bursts.get(1), Some(Burst::Io { .. }))
+    }
+
+    /// Calculate return time of process from its current IO burst against
+    /// global clock.
+    pub fn calc_return_time(&mut self, global_clock: i32) -> Result<(), SimError> {
+        // Get current IO burst from top of Queue
+        let process_burst = match self.process_bursts.front() {
+            Some(Burst::Io { duration, .. }) => *duration,
+            _ => return Err(self.burst_error(Operation::CalcReturnTime, global_clock)),
         };
 
         // Save to struct member for later.
         self.return_from_io_time = global_clock + process_burst;
+        Ok(())
     }
 
-    pub fn ready_next_io(&mut self) {
-        /*
-            Check's process state if it is current CPU burst, pop's off CPU burst
-            to expose next IO burst.
-         */
-
-        // State of process bursts must be odd to indicate current CPU burst
-        assert!(self.process_bursts.len() % 2 != 0);
+    /// Check's process state if it is current CPU burst, pop's off CPU burst
+    /// to expose next IO burst.
+    pub fn ready_next_io(&mut self, global_clock: i32) -> Result<(), SimError> {
+        match self.process_bursts.front() {
+            // Move process burst to next IO, along with its deadline
+            Some(Burst::Cpu(_)) => {
+                self.process_bursts.pop_front();
+                self.params.burst_deadlines.pop_front();
+                Ok(())
+            }
+            _ => Err(self.burst_error(Operation::ReadyNextIo, global_clock)),
+        }
+    }
 
-        // Move process burst to next CPU
-        self.process_bursts.pop_front();
+    /// Check's process state if it is current IO burst, pop's off IO burst
+    /// to expose next CPU burst.
+    pub fn ready_next_cpu(&mut self, global_clock: i32) -> Result<(), SimError> {
+        match self.process_bursts.front() {
+            // Reset burst complete flag as well
+            Some(Burst::Io { .. }) => {
+                self.process_bursts.pop_front();
+                self.burst_completed = false;
+                Ok(())
+            }
+            _ => Err(self.burst_error(Operation::ReadyNextCpu, global_clock)),
+        }
     }
 
-    pub fn ready_next_cpu(&mut self) {
+    fn burst_error(&self, operation: Operation, global_clock: i32) -> SimError {
         /*
-            Check's process state if it is current IO burst, pop's off IO burst
-            to expose next CPU burst.
-         */
-
-        // State of process bursts must be even to indicate current CPU burst
-        assert!(self.process_bursts.len() % 2 == 0);
+           Error for an operation that found no burst, or a burst of the wrong
+           kind, at the front of the queue.
+        */
+        let process = self.name.clone();
+        match self.process_bursts.front() {
+            None => SimError::NoBurst {
+                process,
+                operation,
+                global_clock,
+            },
+            Some(Burst::Cpu(_)) => SimError::NotIoBurst {
+                process,
+                operation,
+                global_clock,
+            },
+            Some(Burst::Io { .. }) => SimError::NotCpuBurst {
+                process,
+                operation,
+                global_clock,
+            },
+        }
+    }
 
-        // Reset burst complete flag as well
-        self.process_bursts.pop_front();
-        self.burst_completed = false;
+    fn front_duration(&self) -> Option<i32> {
+        self.process_bursts.front().map(Burst::duration)
     }
 }
 
 // Extension of PartialEq
 impl PartialOrd for Process {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.process_bursts
-            .get(0)
-            .unwrap()
-            .partial_cmp(&other.process_bursts.get(0).unwrap())
+        // Processes without bursts left sort first.
+        self.front_duration().partial_cmp(&other.front_duration())
     }
 }
 
 // Allow processes to be compared using <, >, etc using the current CPU burst.
 impl PartialEq for Process {
     fn eq(&self, other: &Self) -> bool {
-        self.process_bursts.get(0).unwrap() == other.process_bursts.get(0).unwrap()
+        self.front_duration() == other.front_duration()
     }
 }
 
 // Allow process information to be pretty printed to the console.
 impl Display for Process {
-    fn fmt(&self, f: &mut Formatter) -> Result {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let duration = |index| {
+            self.process_bursts
+                .get(index)
+                .map(Burst::duration)
+                .unwrap_or(-1)
+        };
         write!(
             f,
             "[ Process \"{}\" Curr CPU: {}, Next CPU: {}, Next IO: {} Last Access: {} Return Time: {} ]",
             self.name,
-            self.process_bursts.get(0).unwrap_or(&-1),
-            self.process_bursts.get(2).unwrap_or(&-1),
-            self.process_bursts.get(1).unwrap_or(&-1),
+            duration(0),
+            duration(2),
+            duration(1),
             self.last_accessed,
             self.return_from_io_time
         )
@@ -140,77 +397,146 @@ impl Display for Process {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::VecDeque;
 
     #[test]
     fn run_process() {
-        let mut process_1 = Process {
-            process_bursts: VecDeque::from(vec![5, 4, 3, 2, 1]),
-            total_process_time: 15,
-            name: "P1".to_string(),
-            ..Default::default()
-        };
+        let mut process_1 = Process::new("P1", Bursts::alternating(5, &[(4, 3), (2, 1)]));
 
         // Time quanta < Current CPU burst, no time is left over
-        let left_over = process_1.run(4, 10);
+        let left_over = process_1.run(4, 10).unwrap();
         assert_eq!(left_over, 0);
 
         // Time quanta > current CPU burst, 1 unit is left over
-        process_1.process_bursts.rotate_left(1);
-        let left_over = process_1.run(5, 15);
-        assert_eq!(left_over, 1)
+        process_1.ready_next_io(14).unwrap();
+        process_1.ready_next_cpu(14).unwrap();
+        let left_over = process_1.run(4, 15).unwrap();
+        assert_eq!(left_over, 1);
+        assert_eq!(process_1.last_accessed, 17)
+    }
+
+    #[test]
+    fn preempt_restores_burst() {
+        let mut process = Process::new("P1", Bursts::alternating(5, &[(4, 3)]));
+
+        // Ran 2 units of a time-quanta of 8 that would have completed the burst.
+        process.run(8, 10).unwrap();
+        process.preempt(3, 12).unwrap();
+        assert_eq!(process.current_burst(), Some(3));
+        assert!(!process.burst_completed);
+        assert_eq!(process.last_accessed, 11);
+
+        // Running the rest of the burst leaves the unused time over again.
+        assert_eq!(process.run(5, 20).unwrap(), 2);
+        assert_eq!(process.last_accessed, 22);
+    }
+
+    #[test]
+    fn dropping_work_shortens_the_process() {
+        let mut process =
+            Process::new("P1", Bursts::alternating(6, &[(2, 6)])).with_params(SchedulingParams {
+                burst_deadlines: VecDeque::from(vec![4, 20]),
+                ..Default::default()
+            });
+
+        // Ran 4 units of the first burst, the other 2 are dropped.
+        process.run(4, 0).unwrap();
+        process.skip_burst(4).unwrap();
+        assert!(process.burst_completed);
+        assert_eq!(process.total_process_time, 12);
+        process.ready_next_io(4).unwrap();
+        assert_eq!(process.current_deadline(), Some(20));
+
+        // Nothing is left of the process after an abort.
+        process.abort(10);
+        assert!(process.bursts().is_empty());
+        assert_eq!(process.total_process_time, 4);
+        assert_eq!(process.current_deadline(), None);
+        assert_eq!(process.last_accessed, 9);
     }
 
     #[test]
     fn run_return_calc() {
-        let mut process = Process {
-            process_bursts: VecDeque::from(vec![5, 4, 3]),
-            total_process_time: 12,
-            name: "P2".to_string(),
-            ..Default::default()
-        };
+        let mut process = Process::new("P2", Bursts::alternating(5, &[(4, 3)]));
 
-        // Calculate remaining time and access
-        process.calc_return_time(10);
-        assert_eq!(process.return_from_io_time, 15);
+        // Current burst is CPU, there is no return time to calculate
+        assert!(process.calc_return_time(10).is_err());
 
-        // Rotate and calculate anew
-        process.process_bursts.rotate_left(1);
-        process.calc_return_time(15);
+        // Move on to the IO burst and calculate anew
+        process.ready_next_io(10).unwrap();
+        process.calc_return_time(15).unwrap();
         assert_eq!(process.return_from_io_time, 19);
     }
 
     #[test]
     fn run_get_next() {
-        let mut process = Process {
-            process_bursts: VecDeque::from(vec![6, 2, 1]),
-            total_process_time: 6 + 2 + 1,
-            name: "P3".to_string(),
-            ..Default::default()
-        };
-
-        process.run(6, 10);
-        process.process_bursts.pop_front();
-        process.calc_return_time(10);
-        process.process_bursts.pop_front();
+        let mut process = Process::new("P3", Bursts::alternating(6, &[(2, 1)]));
+
+        process.run(6, 10).unwrap();
+        assert!(process.has_io_next());
+        process.ready_next_io(16).unwrap();
+        process.calc_return_time(16).unwrap();
+        process.ready_next_cpu(18).unwrap();
+        assert_eq!(process.current_burst(), Some(1));
+        assert!(!process.has_io_next());
     }
 
     #[test]
     fn check_ordering() {
-        let process = Process {
-            process_bursts: VecDeque::from(vec![6, 2, 1]),
-            total_process_time: 6 + 2 + 1,
-            name: "P3".to_string(),
-            ..Default::default()
-        };
+        let process = Process::new("P3", Bursts::alternating(6, &[(2, 1)]));
 
-        let process_2 = Process {
-            process_bursts: VecDeque::from(vec![7, 2, 1]),
-            total_process_time: 6 + 2 + 1,
-            name: "P3".to_string(),
-            ..Default::default()
-        };
+        let process_2 = Process::new("P3", Bursts::alternating(7, &[(2, 1)]));
 
         assert!(process < process_2);
     }
+
+    #[test]
+    fn bad_states_are_errors() {
+        let mut process = Process::new("P4", Bursts::alternating(6, &[(2, 1)]));
+
+        assert_eq!(
+            process.run(-1, 3),
+            Err(SimError::NegativeQuanta {
+                process: "P4".to_string(),
+                operation: Operation::Run,
+                global_clock: 3,
+                time_quanta: -1,
+            })
+        );
+
+        // Current burst is CPU, there is no IO burst to move past.
+        assert_eq!(
+            process.ready_next_cpu(7),
+            Err(SimError::NotIoBurst {
+                process: "P4".to_string(),
+                operation: Operation::ReadyNextCpu,
+                global_clock: 7,
+            })
+        );
+
+        process.process_bursts = Bursts::from_phases(Vec::new());
+        let error = process.run(5, 12).unwrap_err();
+        assert_eq!(error.operation(), Operation::Run);
+        assert_eq!(
+            error.to_string(),
+            "process \"P4\" failed to run at global clock 12: no bursts left"
+        );
+
+        // Comparing processes without bursts does not panic.
+        assert_eq!(
+            process.partial_cmp(&Process::new("P5", Bursts::from_phases(Vec::new()))),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn processes_get_the_default_tickets() {
+        assert_eq!(
+            SchedulingParams::default().tickets,
+            Process::DEFAULT_TICKETS
+        );
+        assert_eq!(
+            Process::new("P1", Bursts::cpu(1)).params.tickets,
+            Process::DEFAULT_TICKETS
+        );
+    }
 }
@@ -0,0 +1,529 @@
+//! Results of a simulation run.
+
+use crate::process::Process;
+use std::fmt::{Display, Formatter, Result};
+use tabular::{Row, Table};
+
+/// Times measured for a single process over the course of a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessMetrics {
+    /// Name of the process.
+    pub name: String,
+    /// Time from arrival until the process first got on the CPU.
+    pub response_time: i32,
+    /// Time spent in the ready queue, turnaround time less CPU and IO bursts.
+    pub waiting_time: i32,
+    /// Time from arrival until the last time the process was on the CPU.
+    pub turnaround_time: i32,
+    /// Longest time spent in the ready queue in one go.
+    pub longest_wait: i32,
+    /// Number of times the process got on a CPU other than the one it last ran on.
+    pub migrations: usize,
+}
+
+impl ProcessMetrics {
+    /// Metrics of a finished process.
+    pub fn new(process: &Process) -> ProcessMetrics {
+        let turnaround_time = process.last_accessed - process.arrival_time;
+        ProcessMetrics {
+            name: process.name.clone(),
+            response_time: process.first_accessed.unwrap_or(process.arrival_time)
+                - process.arrival_time,
+            waiting_time: turnaround_time - process.total_process_time,
+            turnaround_time,
+            longest_wait: process.longest_wait,
+            migrations: process.migrations,
+        }
+    }
+}
+
+/// How a process did against its deadlines, see [`Process::deadline_outcomes`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadlineMetrics {
+    /// Name of the process.
+    pub name: String,
+    /// Number of deadlines the process met or missed.
+    pub deadlines: usize,
+    /// Number of deadlines missed, late or dropped work included.
+    pub misses: usize,
+    /// Largest completion time less deadline, negative when every deadline was met
+    /// early.
+    pub max_lateness: i32,
+    /// Time units of lateness over all deadlines, early ones counting as 0.
+    pub total_tardiness: i32,
+}
+
+impl DeadlineMetrics {
+    /// Deadline metrics of a finished process, None if it had no deadlines.
+    pub fn new(process: &Process) -> Option<DeadlineMetrics> {
+        let outcomes = &process.deadline_outcomes;
+        Some(DeadlineMetrics {
+            name: process.name.clone(),
+            deadlines: outcomes.len(),
+            misses: outcomes.iter().filter(|outcome| outcome.missed()).count(),
+            max_lateness: outcomes.iter().map(|outcome| outcome.lateness()).max()?,
+            total_tardiness: outcomes.iter().map(|outcome| outcome.tardiness()).sum(),
+        })
+    }
+}
+
+/// Share of the CPU a process is entitled to against the share it got, for
+/// proportional-share schedulers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareMetrics {
+    /// Name of the process.
+    pub name: String,
+    /// Fraction of the CPU the process is entitled to, e.g. by its tickets.
+    pub entitled_share: f32,
+    /// Fraction of the CPU time the process got while every process was in the
+    /// system.
+    pub cpu_share: f32,
+}
+
+/// How far the CPU share of every process was from the share it is entitled to,
+/// at a point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareSample {
+    /// Global clock of the sample.
+    pub time: i32,
+    /// Share of the CPU time every process got so far, less the share it is
+    /// entitled to, in the order of [`SimulationResult::shares`].
+    pub errors: Vec<f32>,
+}
+
+/// Load of every CPU at a point in time, with ready queue(s) per CPU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadSample {
+    /// Global clock of the sample.
+    pub time: i32,
+    /// Ready processes queued for every CPU plus the one running on it, in CPU
+    /// order.
+    pub loads: Vec<usize>,
+}
+
+impl LoadSample {
+    /// Load of the busiest CPU less that of the least busy one.
+    pub fn imbalance(&self) -> usize {
+        let max = self.loads.iter().max().copied().unwrap_or(0);
+        let min = self.loads.iter().min().copied().unwrap_or(0);
+        max - min
+    }
+}
+
+/// A stretch of time a process spent on a CPU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuRun {
+    /// Name of the process.
+    pub process: String,
+    /// Global clock the process started running at, once the context switch was
+    /// paid for.
+    pub start: i32,
+    /// Global clock the process left the CPU at.
+    pub end: i32,
+}
+
+/// Times measured for a single CPU over the course of a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuMetrics {
+    /// Index of the CPU, from 0.
+    pub cpu: usize,
+    /// Time units the CPU spent with nothing to run.
+    pub idle_time: i32,
+    /// Time units the CPU spent on context switches.
+    pub switch_overhead: i32,
+    /// Number of dispatches that put a process other than the last one on the CPU.
+    pub context_switches: usize,
+    /// Fraction of the global clock the CPU spent running processes, context
+    /// switches excluded.
+    pub utilization: f32,
+    /// Every time a process spent on the CPU, in order.
+    pub timeline: Vec<CpuRun>,
+}
+
+impl CpuMetrics {
+    /// Metrics of CPU `cpu` over a simulation that finished at `global_clock`, with
+    /// the CPU idle for `idle_time` of it and busy with `context_switches` context
+    /// switches for `switch_overhead` of it, and processes running on it as in
+    /// `timeline`.
+    pub fn new(
+        cpu: usize,
+        global_clock: i32,
+        idle_time: i32,
+        switch_overhead: i32,
+        context_switches: usize,
+        timeline: Vec<CpuRun>,
+    ) -> CpuMetrics {
+        let cpu_count = global_clock - idle_time - switch_overhead;
+        CpuMetrics {
+            cpu,
+            idle_time,
+            switch_overhead,
+            context_switches,
+            utilization: cpu_count as f32 / global_clock as f32,
+            timeline,
+        }
+    }
+
+    /// Time units the CPU spent running processes, context switches excluded.
+    pub fn busy_time(&self) -> i32 {
+        self.timeline.iter().map(|run| run.end - run.start).sum()
+    }
+}
+
+/// Everything a simulation run produced. Printing it gives the results table.
+#[derive(Debug)]
+pub struct SimulationResult {
+    /// Name of the scheduler that produced this result.
+    pub scheduler: String,
+    /// Value of the global clock when the last process finished.
+    pub global_clock: i32,
+    /// Time units the CPUs spent with nothing to run, over all CPUs.
+    pub idle_time: i32,
+    /// Time units spent on context switches, over all CPUs.
+    pub switch_overhead: i32,
+    /// Number of dispatches that put a process other than the last one on a CPU,
+    /// over all CPUs.
+    pub context_switches: usize,
+    /// Number of times a running process was taken off the CPU for another one.
+    pub preemptions: usize,
+    /// Fraction of the global clock the CPUs spent running processes, context
+    /// switches excluded, averaged over all CPUs.
+    pub cpu_utilization: f32,
+    /// Metrics per CPU, in CPU order.
+    pub cpus: Vec<CpuMetrics>,
+    /// Metrics per process, sorted by process name.
+    pub metrics: Vec<ProcessMetrics>,
+    /// Finished processes, sorted by process name.
+    pub processes: Vec<Process>,
+    /// Deadline metrics of the processes with deadlines, sorted by process name.
+    pub deadlines: Vec<DeadlineMetrics>,
+    /// CPU shares per process, sorted by process name. Empty unless the scheduler
+    /// is a proportional-share one.
+    pub shares: Vec<ShareMetrics>,
+    /// Share errors at the end of every time a process spent on the CPU while every
+    /// process was present. Empty unless the scheduler is a proportional-share one.
+    pub share_errors: Vec<ShareSample>,
+    /// Load of every CPU whenever it changed. Empty unless the scheduler keeps
+    /// ready queue(s) per CPU.
+    pub loads: Vec<LoadSample>,
+}
+
+impl SimulationResult {
+    /// Collect the result of a simulation that finished `processes` at
+    /// `global_clock` on `cpus`, with `preemptions` processes taken off a CPU.
+    pub fn new(
+        scheduler: &str,
+        global_clock: i32,
+        cpus: Vec<CpuMetrics>,
+        preemptions: usize,
+        mut processes: Vec<Process>,
+    ) -> SimulationResult {
+        // Sort vector of processes by name
+        processes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let idle_time = cpus.iter().map(|cpu| cpu.idle_time).sum();
+        let switch_overhead = cpus.iter().map(|cpu| cpu.switch_overhead).sum();
+        let cpu_time = cpus.len() as i32 * global_clock;
+        let cpu_count = cpu_time - idle_time - switch_overhead;
+        SimulationResult {
+            scheduler: scheduler.to_string(),
+            global_clock,
+            idle_time,
+            switch_overhead,
+            context_switches: cpus.iter().map(|cpu| cpu.context_switches).sum(),
+            preemptions,
+            cpu_utilization: cpu_count as f32 / cpu_time as f32,
+            cpus,
+            metrics: processes.iter().map(ProcessMetrics::new).collect(),
+            deadlines: processes.iter().filter_map(DeadlineMetrics::new).collect(),
+            processes,
+            shares: Vec::new(),
+            share_errors: Vec::new(),
+            loads: Vec::new(),
+        }
+    }
+
+    /// Mean response time over all processes.
+    pub fn average_response_time(&self) -> f32 {
+        self.average(|metrics| metrics.response_time)
+    }
+
+    /// Mean waiting time over all processes.
+    pub fn average_waiting_time(&self) -> f32 {
+        self.average(|metrics| metrics.waiting_time)
+    }
+
+    /// Mean turnaround time over all processes.
+    pub fn average_turnaround_time(&self) -> f32 {
+        self.average(|metrics| metrics.turnaround_time)
+    }
+
+    /// Number of deadlines missed over all processes.
+    pub fn deadline_misses(&self) -> usize {
+        self.deadlines.iter().map(|metrics| metrics.misses).sum()
+    }
+
+    /// Number of times a process got on a CPU other than the one it last ran on,
+    /// over all processes.
+    pub fn migrations(&self) -> usize {
+        self.metrics.iter().map(|metrics| metrics.migrations).sum()
+    }
+
+    /// Imbalance between the CPU loads, weighted by how long it lasted, over the
+    /// whole run. 0 unless the scheduler keeps ready queue(s) per CPU.
+    pub fn average_imbalance(&self) -> f32 {
+        let ends = self
+            .loads
+            .iter()
+            .skip(1)
+            .map(|sample| sample.time)
+            .chain(std::iter::once(self.global_clock));
+        let total: i64 = self
+            .loads
+            .iter()
+            .zip(ends)
+            .map(|(sample, end)| sample.imbalance() as i64 * (end - sample.time) as i64)
+            .sum();
+        if self.global_clock > 0 {
+            total as f32 / self.global_clock as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// Longest time any process spent in the ready queue in one go.
+    pub fn longest_wait(&self) -> i32 {
+        self.metrics
+            .iter()
+            .map(|metrics| metrics.longest_wait)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn average(&self, time: impl Fn(&ProcessMetrics) -> i32) -> f32 {
+        let total: f32 = self.metrics.iter().map(|m| time(m) as f32).sum();
+        total / self.metrics.len() as f32
+    }
+}
+
+// Print the results table: Process Name, Response Time, Wait Time, Turnaround Time,
+// Longest Wait. The last row holds the averages, and the longest wait overall.
+impl Display for SimulationResult {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let mut table = Table::new("{:<} {:<} {:<} {:<} {:<}");
+        table.add_heading("Process Scheduler Results");
+        table.add_row(
+            Row::new()
+                .with_cell("Name")
+                .with_cell("Tr")
+                .with_cell("Tw")
+                .with_cell("Ttr")
+                .with_cell("Tw max"),
+        );
+
+        for metrics in self.metrics.iter() {
+            table.add_row(
+                Row::new()
+                    .with_cell(&metrics.name)
+                    .with_cell(metrics.response_time)
+                    .with_cell(metrics.waiting_time)
+                    .with_cell(metrics.turnaround_time)
+                    .with_cell(metrics.longest_wait),
+            );
+        }
+
+        table.add_row(
+            Row::new()
+                .with_cell("Averages")
+                .with_cell(self.average_response_time())
+                .with_cell(self.average_waiting_time())
+                .with_cell(self.average_turnaround_time())
+                .with_cell(self.longest_wait()),
+        );
+
+        writeln!(f, "{} Results", self.scheduler)?;
+        writeln!(f, "Global Clock: {}", self.global_clock)?;
+        writeln!(f, "Idle Time: {}", self.idle_time)?;
+        writeln!(f, "Context Switches: {}", self.context_switches)?;
+        writeln!(f, "Context Switch Overhead: {}", self.switch_overhead)?;
+        writeln!(f, "Preemptions: {}", self.preemptions)?;
+        writeln!(f, "{}", table)?;
+
+        if !self.deadlines.is_empty() {
+            let mut deadlines = Table::new("{:<} {:<} {:<} {:<} {:<}");
+            deadlines.add_heading("Deadlines");
+            deadlines.add_row(
+                Row::new()
+                    .with_cell("Name")
+                    .with_cell("Deadlines")
+                    .with_cell("Missed")
+                    .with_cell("Lateness max")
+                    .with_cell("Tardiness"),
+            );
+            for metrics in self.deadlines.iter() {
+                deadlines.add_row(
+                    Row::new()
+                        .with_cell(&metrics.name)
+                        .with_cell(metrics.deadlines)
+                        .with_cell(metrics.misses)
+                        .with_cell(metrics.max_lateness)
+                        .with_cell(metrics.total_tardiness),
+                );
+            }
+            deadlines.add_row(
+                Row::new()
+                    .with_cell("Totals")
+                    .with_cell(self.deadlines.iter().map(|m| m.deadlines).sum::<usize>())
+                    .with_cell(self.deadline_misses())
+                    .with_cell(
+                        self.deadlines
+                            .iter()
+                            .map(|m| m.max_lateness)
+                            .max()
+                            .unwrap_or(0),
+                    )
+                    .with_cell(
+                        self.deadlines
+                            .iter()
+                            .map(|m| m.total_tardiness)
+                            .sum::<i32>(),
+                    ),
+            );
+            writeln!(f, "{}", deadlines)?;
+        }
+
+        if !self.shares.is_empty() {
+            let mut shares = Table::new("{:<} {:<} {:<}");
+            shares.add_heading("CPU Share While Every Process Was Present");
+            shares.add_row(
+                Row::new()
+                    .with_cell("Name")
+                    .with_cell("Entitled")
+                    .with_cell("Achieved"),
+            );
+            for share in self.shares.iter() {
+                shares.add_row(
+                    Row::new()
+                        .with_cell(&share.name)
+                        .with_cell(format!("{:.1}%", share.entitled_share * 100f32))
+                        .with_cell(format!("{:.1}%", share.cpu_share * 100f32)),
+                );
+            }
+            writeln!(f, "{}", shares)?;
+        }
+
+        if !self.share_errors.is_empty() {
+            // About ten rows, the last sample always included.
+            let mut errors = Table::new(&"{:<} ".repeat(self.shares.len() + 1));
+            errors.add_heading("Share Error Over Time");
+            let mut heading = Row::new().with_cell("Time");
+            for share in self.shares.iter() {
+                heading.add_cell(&share.name);
+            }
+            errors.add_row(heading);
+
+            let every = self.share_errors.len().div_ceil(10);
+            let last = self.share_errors.len() - 1;
+            for (index, sample) in self.share_errors.iter().enumerate() {
+                if index % every != every - 1 && index != last {
+                    continue;
+                }
+                let mut row = Row::new().with_cell(sample.time);
+                for error in sample.errors.iter() {
+                    row.add_cell(format!("{:+.1}%", error * 100f32));
+                }
+                errors.add_row(row);
+            }
+            writeln!(f, "{}", errors)?;
+        }
+
+        // Only worth a table of its own with more than one CPU.
+        if self.cpus.len() > 1 {
+            let mut cpus = Table::new("{:<} {:<} {:<} {:<} {:<} {:<}");
+            cpus.add_heading("CPUs");
+            cpus.add_row(
+                Row::new()
+                    .with_cell("CPU")
+                    .with_cell("Busy")
+                    .with_cell("Idle")
+                    .with_cell("Switches")
+                    .with_cell("Overhead")
+                    .with_cell("Utilization"),
+            );
+            for metrics in self.cpus.iter() {
+                cpus.add_row(
+                    Row::new()
+                        .with_cell(metrics.cpu)
+                        .with_cell(metrics.busy_time())
+                        .with_cell(metrics.idle_time)
+                        .with_cell(metrics.context_switches)
+                        .with_cell(metrics.switch_overhead)
+                        .with_cell(format!("{:.1}%", metrics.utilization * 100f32)),
+                );
+            }
+            writeln!(f, "{}", cpus)?;
+
+            let mut migrations = Table::new("{:<} {:<} {:<}");
+            migrations.add_heading("Migrations");
+            migrations.add_row(
+                Row::new()
+                    .with_cell("Name")
+                    .with_cell("Migrations")
+                    .with_cell("Ttr"),
+            );
+            for metrics in self.metrics.iter() {
+                migrations.add_row(
+                    Row::new()
+                        .with_cell(&metrics.name)
+                        .with_cell(metrics.migrations)
+                        .with_cell(metrics.turnaround_time),
+                );
+            }
+            migrations.add_row(
+                Row::new()
+                    .with_cell("Totals")
+                    .with_cell(self.migrations())
+                    .with_cell(self.average_turnaround_time()),
+            );
+            writeln!(f, "{}", migrations)?;
+
+            writeln!(f, "CPU Timelines")?;
+            for metrics in self.cpus.iter() {
+                write!(f, "CPU {}:", metrics.cpu)?;
+                for run in metrics.timeline.iter() {
+                    write!(f, " [{}-{} {}]", run.start, run.end, run.process)?;
+                }
+                writeln!(f)?;
+            }
+            writeln!(f)?;
+        }
+
+        if !self.loads.is_empty() {
+            // About ten rows, the last sample always included.
+            let mut loads = Table::new(&"{:<} ".repeat(self.cpus.len() + 2));
+            loads.add_heading("CPU Load Over Time");
+            let mut heading = Row::new().with_cell("Time");
+            for metrics in self.cpus.iter() {
+                heading.add_cell(format!("CPU {}", metrics.cpu));
+            }
+            loads.add_row(heading.with_cell("Imbalance"));
+
+            let every = self.loads.len().div_ceil(10);
+            let last = self.loads.len() - 1;
+            for (index, sample) in self.loads.iter().enumerate() {
+                if index % every != every - 1 && index != last {
+                    continue;
+                }
+                let mut row = Row::new().with_cell(sample.time);
+                for load in sample.loads.iter() {
+                    row.add_cell(load);
+                }
+                loads.add_row(row.with_cell(sample.imbalance()));
+            }
+            writeln!(f, "{}", loads)?;
+            writeln!(f, "Average Imbalance: {:.2}", self.average_imbalance())?;
+        }
+
+        write!(f, "CPU Utilization: {}%", self.cpu_utilization * 100f32)
+    }
+}
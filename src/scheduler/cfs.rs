@@ -0,0 +1,318 @@
+use super::{Context, Migration, Scheduler};
+use crate::process::Process;
+use std::collections::{BTreeMap, HashMap};
+
+// Weight of a process with nice value 0.
+pub(super) const NICE_0_LOAD: u64 = 1024;
+// Virtual runtime a process with nice value 0 gathers per time unit, so that
+// processes with large weights still advance.
+pub(super) const VRUNTIME_SCALE: u64 = 1024;
+
+/// Completely Fair Scheduler, after the Linux one. Every process gathers virtual
+/// runtime as it runs, slower the larger its weight (see [`Cfs::weight`]), and the
+/// ready process with the least virtual runtime runs next. Ready processes are kept
+/// in a tree ordered by virtual runtime, ties go to the process that has been ready
+/// the longest.
+///
+/// Every `sched_latency` time units each ready process gets a slice of the CPU in
+/// proportion to its weight, no shorter than `min_granularity`. With too many ready
+/// processes for that, the period stretches to `min_granularity` per process.
+///
+/// The scheduler keeps `min_vruntime`, which only ever grows, the least virtual
+/// runtime of the running and ready processes. New processes start at it, and a
+/// process waking from IO is placed no further than half a `sched_latency` behind
+/// it: it gets ahead of the processes that kept running, but cannot make up for all
+/// the time it was away.
+pub struct Cfs {
+    timeline: BTreeMap<(u64, u64), Process>,
+    sched_latency: i32,
+    min_granularity: i32,
+    // Virtual runtime of every process, kept while it is in IO.
+    vruntime: HashMap<String, u64>,
+    min_vruntime: u64,
+    // Number of processes put in the timeline so far, to order ties.
+    enqueued: u64,
+    // CPU time of the time slices handed out to processes not back yet, by name.
+    running_slices: HashMap<String, i32>,
+}
+
+impl Cfs {
+    /// Length of the period every ready process runs once in, unless set otherwise.
+    pub const DEFAULT_SCHED_LATENCY: i32 = 24;
+    /// Shortest time slice, unless set otherwise.
+    pub const DEFAULT_MIN_GRANULARITY: i32 = 3;
+
+    /// Weights of nice values -20 to 19 of the Linux scheduler. Every nice level is
+    /// worth about 10% of the CPU against a process one level apart.
+    #[rustfmt::skip]
+    pub const NICE_TO_WEIGHT: [u32; 40] = [
+        /* -20 */ 88761, 71755, 56483, 46273, 36291,
+        /* -15 */ 29154, 23254, 18705, 14949, 11916,
+        /* -10 */ 9548, 7620, 6100, 4904, 3906,
+        /*  -5 */ 3121, 2501, 1991, 1586, 1277,
+        /*   0 */ 1024, 820, 655, 526, 423,
+        /*   5 */ 335, 272, 215, 172, 137,
+        /*  10 */ 110, 87, 70, 56, 45,
+        /*  15 */ 36, 29, 23, 18, 15,
+    ];
+
+    /// New scheduler with empty ready queue(s).
+    pub fn new() -> Cfs {
+        Cfs {
+            timeline: BTreeMap::new(),
+            sched_latency: Cfs::DEFAULT_SCHED_LATENCY,
+            min_granularity: Cfs::DEFAULT_MIN_GRANULARITY,
+            vruntime: HashMap::new(),
+            min_vruntime: 0,
+            enqueued: 0,
+            running_slices: HashMap::new(),
+        }
+    }
+
+    /// Run every ready process once every `sched_latency` time units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sched_latency` is not positive.
+    pub fn with_sched_latency(mut self, sched_latency: i32) -> Cfs {
+        assert!(sched_latency > 0, "sched_latency must be positive");
+        self.sched_latency = sched_latency;
+        self
+    }
+
+    /// Give no process a time slice shorter than `min_granularity`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_granularity` is not positive.
+    pub fn with_min_granularity(mut self, min_granularity: i32) -> Cfs {
+        assert!(min_granularity > 0, "min_granularity must be positive");
+        self.min_granularity = min_granularity;
+        self
+    }
+
+    /// Weight of a process with nice value `nice`, clamped to -20 to 19.
+    pub fn weight(nice: i32) -> u32 {
+        Cfs::NICE_TO_WEIGHT[(nice.clamp(-20, 19) + 20) as usize]
+    }
+
+    fn slice(&self, process: &Process) -> i32 {
+        /*
+            Time slice of `process`, picked from the timeline, in proportion to its
+            weight against all processes ready to run.
+        */
+        let running = self.timeline.len() as i64 + 1;
+        let period = if running * self.min_granularity as i64 > self.sched_latency as i64 {
+            running * self.min_granularity as i64
+        } else {
+            self.sched_latency as i64
+        };
+
+        let weight = Cfs::weight(process.params.nice) as i64;
+        let total_weight: i64 = weight
+            + self
+                .timeline
+                .values()
+                .map(|process| Cfs::weight(process.params.nice) as i64)
+                .sum::<i64>();
+        let slice = period * weight / total_weight;
+        (slice as i32).max(self.min_granularity)
+    }
+
+    fn charge(&mut self, process: &Process) -> u64 {
+        /*
+            Add the time slice of the process, weighted, to its virtual runtime.
+        */
+        let slice = self.running_slices.remove(&process.name).unwrap_or(0);
+        let delta =
+            slice as u64 * VRUNTIME_SCALE * NICE_0_LOAD / Cfs::weight(process.params.nice) as u64;
+        let vruntime = self
+            .vruntime
+            .entry(process.name.clone())
+            .or_insert(self.min_vruntime);
+        *vruntime += delta;
+        let vruntime = *vruntime;
+
+        // The charged process is off the CPU, min_vruntime follows whichever of it
+        // and the timeline is behind.
+        let leftmost = self.timeline.keys().next().map(|&(vruntime, _)| vruntime);
+        let least = leftmost.map_or(vruntime, |leftmost| leftmost.min(vruntime));
+        self.min_vruntime = self.min_vruntime.max(least);
+        vruntime
+    }
+
+    fn enqueue(&mut self, process: Process, vruntime: u64) {
+        self.vruntime.insert(process.name.clone(), vruntime);
+        self.timeline.insert((vruntime, self.enqueued), process);
+        self.enqueued += 1;
+    }
+}
+
+impl Default for Cfs {
+    fn default() -> Cfs {
+        Cfs::new()
+    }
+}
+
+impl Scheduler for Cfs {
+    fn name(&self) -> &str {
+        "Completely Fair Scheduler"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        let vruntime = match self.vruntime.get(&process.name) {
+            // Waking from IO, half a sched_latency of credit at most.
+            Some(&vruntime) => {
+                let credit = self.sched_latency as u64 * VRUNTIME_SCALE / 2;
+                vruntime.max(self.min_vruntime.saturating_sub(credit))
+            }
+            None => self.min_vruntime,
+        };
+        self.enqueue(process, vruntime);
+    }
+
+    fn pick_next(&mut self, _ctx: &Context) -> Option<(Process, i32)> {
+        let key = *self.timeline.keys().next()?;
+        let process = self.timeline.remove(&key)?;
+        let slice = self.slice(&process);
+
+        // A process without a CPU burst fails when the engine runs it.
+        self.running_slices.insert(
+            process.name.clone(),
+            slice.min(process.current_burst().unwrap_or(0)),
+        );
+        Some((process, slice))
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, _ctx: &Context) {
+        let vruntime = self.charge(&process);
+        self.enqueue(process, vruntime);
+    }
+
+    fn on_burst_complete(&mut self, process: &Process, _ctx: &Context) {
+        self.charge(process);
+    }
+
+    fn take_migrating(&mut self, _ctx: &Context) -> Option<(Process, Migration)> {
+        // The virtual runtime goes along relative to min_vruntime, as the run queues
+        // keep time apart.
+        let key = *self.timeline.keys().next()?;
+        let process = self.timeline.remove(&key)?;
+        let vruntime = self.vruntime.remove(&process.name).unwrap_or(key.0);
+        Some((
+            process,
+            Migration::new(vruntime as i64 - self.min_vruntime as i64),
+        ))
+    }
+
+    fn admit_migrated(&mut self, process: Process, migration: Migration, ctx: &Context) {
+        let relative = match migration.into_state::<i64>() {
+            Some(relative) => relative,
+            None => return self.on_ready(process, ctx),
+        };
+        let vruntime = (self.min_vruntime as i64 + relative).max(0) as u64;
+        self.enqueue(process, vruntime);
+    }
+
+    fn share_weights(&self, processes: &[Process]) -> Option<Vec<f64>> {
+        Some(
+            processes
+                .iter()
+                .map(|process| Cfs::weight(process.params.nice) as f64)
+                .collect(),
+        )
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Timeline", self.timeline.values().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::{simulate, simulate_with, SimulationConfig};
+    use crate::observer::{EventLog, SimEvent};
+    use crate::process::SchedulingParams;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn shares_follow_nice_weights() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("P1", Bursts::cpu(1000)).with_params(SchedulingParams {
+                    nice: -5,
+                    ..Default::default()
+                }),
+                Process::new("P2", Bursts::cpu(1000)),
+                Process::new("P3", Bursts::cpu(1000)).with_params(SchedulingParams {
+                    nice: 5,
+                    ..Default::default()
+                }),
+            ])
+        };
+
+        let result = simulate(&mut Cfs::new(), workload()).unwrap();
+        let total = (3121 + 1024 + 335) as f32;
+        let entitled: Vec<f32> = result
+            .shares
+            .iter()
+            .map(|share| share.entitled_share)
+            .collect();
+        assert_eq!(
+            entitled,
+            vec![3121.0 / total, 1024.0 / total, 335.0 / total]
+        );
+        for share in result.shares.iter() {
+            assert!((share.cpu_share - share.entitled_share).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn slices_follow_latency_and_granularity() {
+        let slices = |cfs: &mut Cfs, count: usize| {
+            let processes = (1..=count)
+                .map(|index| Process::new(&format!("P{}", index), Bursts::cpu(100)))
+                .collect();
+            let mut log = EventLog::default();
+            simulate_with(
+                cfs,
+                processes,
+                &SimulationConfig::default(),
+                &mut [&mut log],
+            )
+            .unwrap();
+            match log
+                .events
+                .iter()
+                .find(|event| matches!(event, SimEvent::Dispatch { .. }))
+            {
+                Some(SimEvent::Dispatch { time_quanta, .. }) => *time_quanta,
+                _ => panic!("no dispatch"),
+            }
+        };
+
+        // Two processes split the latency, ten stretch the period to the granularity.
+        assert_eq!(slices(&mut Cfs::new(), 2), 12);
+        assert_eq!(slices(&mut Cfs::new().with_sched_latency(40), 2), 20);
+        assert_eq!(slices(&mut Cfs::new(), 10), 3);
+        assert_eq!(slices(&mut Cfs::new().with_min_granularity(5), 10), 5);
+    }
+
+    #[test]
+    fn places_waking_process_near_min_vruntime() {
+        let workload = VecDeque::from(vec![
+            Process::new("P1", Bursts::cpu(12).io(0, 500).cpu(100)),
+            Process::new("P2", Bursts::cpu(1000)),
+        ]);
+
+        // Back from IO, P1 gets half a latency ahead of P2 and then takes turns with
+        // it, rather than running its whole burst to make up for the time away.
+        let result = simulate(&mut Cfs::new(), workload).unwrap();
+        let p1 = &result.metrics[0];
+        assert_eq!(p1.name, "P1");
+        assert!(p1.turnaround_time > 512 + 180, "{}", p1.turnaround_time);
+        assert!(p1.turnaround_time < 512 + 220, "{}", p1.turnaround_time);
+    }
+}
@@ -0,0 +1,164 @@
+use super::{Context, Overrun, Scheduler};
+use crate::process::Process;
+use std::collections::VecDeque;
+
+/// Preemptive Earliest Deadline First. The ready process whose CPU burst is due
+/// first (see [`Process::current_deadline`]) runs its whole CPU burst, ties are
+/// broken first come first serve and processes without a deadline go last. A
+/// process that arrives or returns from IO with an earlier deadline than the
+/// running process takes the CPU from it.
+///
+/// Late work runs to completion unless set otherwise, see [`Overrun`].
+///
+/// Invariant of this scheduler is that the ready queue is always sorted by
+/// deadline.
+#[derive(Default)]
+pub struct Edf {
+    ready_queue: VecDeque<Process>,
+    overrun: Overrun,
+}
+
+impl Edf {
+    /// New scheduler with empty ready queue(s), running late work to completion.
+    pub fn new() -> Edf {
+        Default::default()
+    }
+
+    /// Handle CPU bursts past their deadline with `overrun`.
+    pub fn with_overrun(mut self, overrun: Overrun) -> Edf {
+        self.overrun = overrun;
+        self
+    }
+}
+
+// Deadlines in order, no deadline last.
+fn due(process: &Process) -> (bool, i32) {
+    match process.current_deadline() {
+        Some(deadline) => (false, deadline),
+        None => (true, 0),
+    }
+}
+
+impl Scheduler for Edf {
+    fn name(&self) -> &str {
+        "Earliest Deadline First"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        // Behind every process due at the same time or before, to maintain invariant.
+        let index = self
+            .ready_queue
+            .iter()
+            .position(|other| due(other) > due(&process))
+            .unwrap_or(self.ready_queue.len());
+        self.ready_queue.insert(index, process);
+    }
+
+    fn pick_next(&mut self, _ctx: &Context) -> Option<(Process, i32)> {
+        let process = self.ready_queue.pop_front()?;
+
+        // Time-quanta is the whole of the current burst, unless preempted. A process
+        // without one fails when the engine runs it.
+        let process_quanta = process.current_burst().unwrap_or(0);
+        Some((process, process_quanta))
+    }
+
+    fn preempts(&self, running: &Process, _remaining: i32, ready: &Process) -> bool {
+        // Ties keep the running process on the CPU.
+        due(ready) < due(running)
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        // Cannot happen as the whole burst is granted, put the process back in line.
+        self.on_ready(process, ctx);
+    }
+
+    fn overrun(&self) -> Overrun {
+        self.overrun
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Deadline", self.ready_queue.iter().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::simulate;
+    use crate::process::{DeadlineOutcome, SchedulingParams};
+
+    #[test]
+    fn preempts_for_earlier_deadline() {
+        let processes = VecDeque::from(vec![
+            Process::new("L", Bursts::cpu(10)).with_params(SchedulingParams {
+                deadline: Some(30),
+                ..Default::default()
+            }),
+            Process::new("U", Bursts::cpu(3))
+                .with_params(SchedulingParams {
+                    deadline: Some(6),
+                    ..Default::default()
+                })
+                .with_arrival_time(2),
+            Process::new("N", Bursts::cpu(1)),
+        ]);
+
+        // U takes over at 2 and completes at 5, L completes at 13, N goes last.
+        let result = simulate(&mut Edf::new(), processes).unwrap();
+        assert_eq!(result.preemptions, 1);
+        let lateness: Vec<(&str, i32)> = result
+            .deadlines
+            .iter()
+            .map(|metrics| (metrics.name.as_str(), metrics.max_lateness))
+            .collect();
+        assert_eq!(lateness, vec![("L", -17), ("U", -1)]);
+        assert_eq!(result.deadline_misses(), 0);
+        assert_eq!(result.metrics[1].turnaround_time, 13);
+    }
+
+    #[test]
+    fn handles_overruns() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("X", Bursts::alternating(6, &[(2, 6)])).with_params(
+                    SchedulingParams {
+                        burst_deadlines: VecDeque::from(vec![4, 20]),
+                        ..Default::default()
+                    },
+                ),
+                Process::new("Y", Bursts::cpu(5)).with_params(SchedulingParams {
+                    deadline: Some(30),
+                    ..Default::default()
+                }),
+            ])
+        };
+        let outcomes = |overrun| {
+            let result = simulate(&mut Edf::new().with_overrun(overrun), workload()).unwrap();
+            result.processes[0].deadline_outcomes.clone()
+        };
+        let outcome = |deadline, completion, dropped| DeadlineOutcome {
+            deadline,
+            completion,
+            dropped,
+        };
+
+        // Late, then back from IO at 8 it takes the CPU from Y.
+        assert_eq!(
+            outcomes(Overrun::Continue),
+            vec![outcome(4, 6, false), outcome(20, 14, false)]
+        );
+        // Cut off at 4, and back from IO at 6.
+        assert_eq!(
+            outcomes(Overrun::SkipBurst),
+            vec![outcome(4, 4, true), outcome(20, 12, false)]
+        );
+        assert_eq!(outcomes(Overrun::AbortJob), vec![outcome(4, 4, true)]);
+
+        let result = simulate(&mut Edf::new().with_overrun(Overrun::AbortJob), workload()).unwrap();
+        assert_eq!(result.global_clock, 9);
+        assert_eq!(result.deadlines[0].misses, 1);
+        assert_eq!(result.deadlines[0].total_tardiness, 0);
+    }
+}
@@ -0,0 +1,411 @@
+use super::cfs::{NICE_0_LOAD, VRUNTIME_SCALE};
+use super::{Cfs, Context, Migration, Scheduler};
+use crate::process::Process;
+use std::collections::{HashMap, VecDeque};
+
+/// Earliest Eligible Virtual Deadline First, the successor of the
+/// [`Cfs`] in Linux. Every process gathers virtual runtime as it runs, weighted by
+/// its nice value like with the Completely Fair Scheduler, and the virtual time of
+/// the scheduler is the weighted average virtual runtime of the running and ready
+/// processes.
+///
+/// A process is eligible once the virtual time reaches its virtual runtime, i.e.
+/// once it received no more than its share of the CPU, and asks for the CPU in
+/// requests of a slice, the time-quanta of the process (see
+/// [`SchedulingParams::time_quanta`](crate::SchedulingParams::time_quanta)) or the
+/// base slice of the scheduler. Its virtual deadline is its eligible time plus the
+/// slice, weighted. The eligible process with the earliest virtual deadline runs
+/// until its request is served, so a shorter slice gets a process on the CPU sooner,
+/// in smaller pieces, without a larger share. A process that arrives or returns from
+/// IO takes the CPU if it would be picked.
+///
+/// The lag of a process, how far its virtual runtime is behind the virtual time, is
+/// kept while it is in IO, and it is placed at the same lag when it returns.
+pub struct Eevdf {
+    ready_queue: VecDeque<Process>,
+    base_slice: i32,
+    entities: HashMap<String, Entity>,
+    // Processes dispatched and not back yet, by name.
+    running: HashMap<String, Running>,
+    // Virtual time when no process is running or ready.
+    virtual_time: i64,
+}
+
+// Scheduling state of a process.
+#[derive(Debug, Clone, Copy)]
+struct Entity {
+    weight: i64,
+    // Virtual time from which the process is eligible, its virtual runtime.
+    eligible: i64,
+    deadline: i64,
+    // Lag at the end of the last CPU burst, while the process is in IO.
+    lag: Option<i64>,
+}
+
+struct Running {
+    start_time: i32,
+    // CPU burst at dispatch, and the part of it handed out.
+    burst: i32,
+    slice: i32,
+}
+
+impl Eevdf {
+    /// Slice of processes that do not ask for a time-quanta of their own, unless
+    /// set otherwise.
+    pub const DEFAULT_BASE_SLICE: i32 = 3;
+
+    /// New scheduler with empty ready queue(s).
+    pub fn new() -> Eevdf {
+        Eevdf {
+            ready_queue: VecDeque::new(),
+            base_slice: Eevdf::DEFAULT_BASE_SLICE,
+            entities: HashMap::new(),
+            running: HashMap::new(),
+            virtual_time: 0,
+        }
+    }
+
+    /// Serve processes that do not ask for a time-quanta of their own in requests
+    /// of `base_slice` units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base_slice` is not positive.
+    pub fn with_base_slice(mut self, base_slice: i32) -> Eevdf {
+        assert!(base_slice > 0, "base slice must be positive");
+        self.base_slice = base_slice;
+        self
+    }
+
+    fn virtual_slice(&self, process: &Process, weight: i64) -> i64 {
+        let slice = process.params.time_quanta.unwrap_or(self.base_slice) as i64;
+        slice * VRUNTIME_SCALE as i64 * NICE_0_LOAD as i64 / weight
+    }
+
+    fn load(&self, running_used: impl Fn(&str, &Running) -> i32) -> Vec<Entity> {
+        /*
+            Ready processes and the running ones, which used `running_used` units of
+            their slice so far.
+        */
+        let mut load: Vec<Entity> = self
+            .ready_queue
+            .iter()
+            .filter_map(|process| self.entities.get(&process.name).copied())
+            .collect();
+        for (name, running) in self.running.iter() {
+            let mut entity = self.entities[name];
+            entity.eligible +=
+                running_used(name, running) as i64 * VRUNTIME_SCALE as i64 * NICE_0_LOAD as i64
+                    / entity.weight;
+            load.push(entity);
+        }
+        load
+    }
+
+    fn load_at(&self, global_clock: i32) -> Vec<Entity> {
+        // Running processes used their slice up to `global_clock`.
+        self.load(|_, running| (global_clock - running.start_time).clamp(0, running.slice))
+    }
+
+    fn average(&self, load: &[Entity]) -> i64 {
+        /*
+            Virtual time, the average virtual runtime of `load` by weight.
+        */
+        let total_weight: i128 = load.iter().map(|entity| entity.weight as i128).sum();
+        if total_weight == 0 {
+            return self.virtual_time;
+        }
+        let weighted: i128 = load
+            .iter()
+            .map(|entity| entity.weight as i128 * entity.eligible as i128)
+            .sum();
+        (weighted.div_euclid(total_weight)) as i64
+    }
+
+    fn place(&self, process: &Process, load: &[Entity]) -> Entity {
+        /*
+            Entity of `process` entering the ready queue next to `load`, at the lag it
+            left with, if any. Adding a process moves the virtual time towards it, so
+            the lag is scaled up for the process to end up at it.
+        */
+        let weight = Cfs::weight(process.params.nice) as i64;
+        let lag = self
+            .entities
+            .get(&process.name)
+            .and_then(|entity| entity.lag)
+            .unwrap_or(0);
+        let total_weight: i64 = load.iter().map(|entity| entity.weight).sum();
+        let lag = if total_weight > 0 {
+            lag * (total_weight + weight) / total_weight
+        } else {
+            lag
+        };
+
+        let eligible = self.average(load) - lag;
+        Entity {
+            weight,
+            eligible,
+            deadline: eligible + self.virtual_slice(process, weight),
+            lag: None,
+        }
+    }
+
+    fn earliest_eligible(&self, global_clock: i32) -> Option<usize> {
+        /*
+            Index of the ready process with the earliest deadline among the eligible
+            ones, ties go to the process that has been ready the longest. Some process
+            is always eligible, as not all of them can be ahead of the average.
+        */
+        let virtual_time = self.average(&self.load_at(global_clock));
+        let entity = |process: &Process| self.entities[&process.name];
+        self.ready_queue
+            .iter()
+            .enumerate()
+            .filter(|(_, process)| entity(process).eligible <= virtual_time)
+            .min_by_key(|(_, process)| entity(process).deadline)
+            .or_else(|| {
+                self.ready_queue
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, process)| entity(process).eligible)
+            })
+            .map(|(index, _)| index)
+    }
+
+    fn charge(&mut self, process: &Process, used: i32) {
+        /*
+            Add `used` units of CPU time, weighted, to the virtual runtime of the
+            process, with a new request once the last one is served.
+        */
+        self.running.remove(&process.name);
+        let mut entity = self.entities[&process.name];
+        entity.eligible += used as i64 * VRUNTIME_SCALE as i64 * NICE_0_LOAD as i64 / entity.weight;
+        if entity.eligible >= entity.deadline {
+            entity.deadline = entity.eligible + self.virtual_slice(process, entity.weight);
+        }
+        self.entities.insert(process.name.clone(), entity);
+    }
+}
+
+impl Default for Eevdf {
+    fn default() -> Eevdf {
+        Eevdf::new()
+    }
+}
+
+impl Scheduler for Eevdf {
+    fn name(&self) -> &str {
+        "Earliest Eligible Virtual Deadline First"
+    }
+
+    fn on_ready(&mut self, process: Process, ctx: &Context) {
+        let mut load = self.load_at(ctx.global_clock());
+        let entity = self.place(&process, &load);
+        load.push(entity);
+        self.virtual_time = self.average(&load);
+        self.entities.insert(process.name.clone(), entity);
+        self.ready_queue.push_back(process);
+    }
+
+    fn pick_next(&mut self, ctx: &Context) -> Option<(Process, i32)> {
+        let index = self.earliest_eligible(ctx.global_clock())?;
+        let process = self.ready_queue.remove(index)?;
+
+        // The rest of the request, in time units.
+        let entity = self.entities[&process.name];
+        let slice = ((entity.deadline - entity.eligible) * entity.weight)
+            .div_euclid(VRUNTIME_SCALE as i64 * NICE_0_LOAD as i64)
+            .max(1) as i32;
+
+        // A process without a CPU burst fails when the engine runs it.
+        let burst = process.current_burst().unwrap_or(0);
+        self.running.insert(
+            process.name.clone(),
+            Running {
+                start_time: ctx.global_clock(),
+                burst,
+                slice: slice.min(burst),
+            },
+        );
+        Some((process, slice))
+    }
+
+    fn preempts(&self, running: &Process, remaining: i32, ready: &Process) -> bool {
+        // Whether `ready` would be picked over the running processes and every ready
+        // one, were it placed now. Now is as far into the slice of `running` as it
+        // used of its burst.
+        let (start_time, used) = match self.running.get(&running.name) {
+            Some(state) => (state.start_time, state.burst - remaining),
+            None => return false,
+        };
+        let mut load = self.load(|name, other| {
+            if name == running.name {
+                used
+            } else {
+                (start_time + used - other.start_time).clamp(0, other.slice)
+            }
+        });
+        let entity = self.place(ready, &load);
+        load.push(entity);
+        let virtual_time = self.average(&load);
+
+        entity.eligible <= virtual_time
+            && load[..load.len() - 1]
+                .iter()
+                .filter(|other| other.eligible <= virtual_time)
+                .all(|other| entity.deadline < other.deadline)
+    }
+
+    fn on_preempted(&mut self, process: Process, _ctx: &Context) {
+        let used = self.running.get(&process.name).map_or(0, |running| {
+            running.burst - process.current_burst().unwrap_or(0)
+        });
+        self.charge(&process, used);
+        self.ready_queue.push_back(process);
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, _ctx: &Context) {
+        let used = self
+            .running
+            .get(&process.name)
+            .map_or(0, |running| running.slice);
+        self.charge(&process, used);
+        self.ready_queue.push_back(process);
+    }
+
+    fn on_burst_complete(&mut self, process: &Process, ctx: &Context) {
+        // Keep the lag for when the process is back from IO, up to two slices either
+        // way.
+        let used = self
+            .running
+            .get(&process.name)
+            .map_or(0, |running| running.slice);
+        self.charge(process, used);
+
+        let mut entity = self.entities[&process.name];
+        let mut load = self.load_at(ctx.global_clock());
+        load.push(entity);
+        self.virtual_time = self.average(&load);
+        let limit = 2 * self.virtual_slice(process, entity.weight);
+        entity.lag = Some((self.virtual_time - entity.eligible).clamp(-limit, limit));
+        self.entities.insert(process.name.clone(), entity);
+    }
+
+    fn take_migrating(&mut self, ctx: &Context) -> Option<(Process, Migration)> {
+        // The lag goes along, and the process is placed at it in its new run queue as
+        // on its return from IO.
+        let virtual_time = self.average(&self.load_at(ctx.global_clock()));
+        let index = self.earliest_eligible(ctx.global_clock())?;
+        let process = self.ready_queue.remove(index)?;
+        let eligible = self
+            .entities
+            .remove(&process.name)
+            .map_or(virtual_time, |entity| entity.eligible);
+        self.virtual_time = self.average(&self.load_at(ctx.global_clock()));
+        Some((process, Migration::new(virtual_time - eligible)))
+    }
+
+    fn admit_migrated(&mut self, process: Process, migration: Migration, ctx: &Context) {
+        if let Some(lag) = migration.into_state::<i64>() {
+            let weight = Cfs::weight(process.params.nice) as i64;
+            self.entities.insert(
+                process.name.clone(),
+                Entity {
+                    weight,
+                    eligible: 0,
+                    deadline: 0,
+                    lag: Some(lag),
+                },
+            );
+        }
+        self.on_ready(process, ctx);
+    }
+
+    fn share_weights(&self, processes: &[Process]) -> Option<Vec<f64>> {
+        Some(
+            processes
+                .iter()
+                .map(|process| Cfs::weight(process.params.nice) as f64)
+                .collect(),
+        )
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Ready", self.ready_queue.iter().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::{simulate, simulate_with, SimulationConfig};
+    use crate::observer::{EventLog, SimEvent};
+    use crate::process::SchedulingParams;
+
+    #[test]
+    fn serves_short_requests_first() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("B1", Bursts::cpu(300)).with_params(SchedulingParams {
+                    time_quanta: Some(30),
+                    ..Default::default()
+                }),
+                Process::new("B2", Bursts::cpu(300)).with_params(SchedulingParams {
+                    time_quanta: Some(30),
+                    ..Default::default()
+                }),
+                Process::new("I", Bursts::alternating(2, &[(10, 2); 10])),
+            ])
+        };
+
+        // The interactive process asks for the base slice and takes the CPU from the
+        // batch processes on its return from IO, where the Completely Fair Scheduler
+        // with the same periods makes it wait for them.
+        let eevdf = simulate(&mut Eevdf::new(), workload()).unwrap();
+        let cfs = simulate(&mut Cfs::new().with_sched_latency(60), workload()).unwrap();
+        assert_eq!(eevdf.metrics[2].name, "I");
+        assert!(eevdf.preemptions > 0);
+        assert!(eevdf.metrics[2].turnaround_time < cfs.metrics[2].turnaround_time);
+    }
+
+    #[test]
+    fn keeps_lag_across_io() {
+        let processes = VecDeque::from(vec![
+            Process::new("B1", Bursts::cpu(100)),
+            Process::new("B2", Bursts::cpu(100)),
+            Process::new("I", Bursts::cpu(2).io(0, 10).cpu(2)).with_params(SchedulingParams {
+                time_quanta: Some(3),
+                ..Default::default()
+            }),
+        ]);
+
+        let mut log = EventLog::default();
+        simulate_with(
+            &mut Eevdf::new().with_base_slice(30),
+            processes,
+            &SimulationConfig::default(),
+            &mut [&mut log],
+        )
+        .unwrap();
+
+        // I ran ahead of both others before its IO, so on its return at 12 it is not
+        // eligible until they caught up, rather than starting over at no lag.
+        let dispatches: Vec<(i32, &str)> = log
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                SimEvent::Dispatch { time, process, .. } => Some((*time, process.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            &dispatches[..4],
+            &[(0, "I"), (2, "B1"), (32, "B2"), (62, "I")]
+        );
+        assert!(!log
+            .events
+            .iter()
+            .any(|event| matches!(event, SimEvent::Preempt { .. })));
+    }
+}
@@ -0,0 +1,45 @@
+use super::{Context, Scheduler};
+use crate::process::Process;
+use std::collections::VecDeque;
+
+/// Non-preemptive First Come First Serve, every process runs its whole CPU burst
+/// in the order it entered the ready queue.
+#[derive(Default)]
+pub struct Fcfs {
+    ready_queue: VecDeque<Process>,
+}
+
+impl Fcfs {
+    /// New scheduler with empty ready queue(s).
+    pub fn new() -> Fcfs {
+        Default::default()
+    }
+}
+
+impl Scheduler for Fcfs {
+    fn name(&self) -> &str {
+        "First-Come-First-Serve"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        self.ready_queue.push_back(process);
+    }
+
+    fn pick_next(&mut self, _ctx: &Context) -> Option<(Process, i32)> {
+        let process = self.ready_queue.pop_front()?;
+
+        // Time-quanta is the whole of the current burst. A process without one
+        // fails when the engine runs it.
+        let process_quanta = process.current_burst().unwrap_or(0);
+        Some((process, process_quanta))
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, _ctx: &Context) {
+        // Cannot happen as the whole burst is granted, keep the process at the front.
+        self.ready_queue.push_front(process);
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Process", self.ready_queue.iter().collect())]
+    }
+}
@@ -0,0 +1,108 @@
+use super::{Context, Scheduler};
+use crate::process::Process;
+use std::collections::VecDeque;
+
+/// How a fixed-priority scheduler ranks periodic tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assignment {
+    /// The shorter the period of the task, the higher its priority, see
+    /// [`SchedulingParams::period`](crate::SchedulingParams::period).
+    RateMonotonic,
+    /// The shorter the relative deadline of the task, the higher its priority, see
+    /// [`Process::relative_deadline`].
+    DeadlineMonotonic,
+}
+
+impl Assignment {
+    /// Name of the assignment, used when reporting results.
+    pub fn label(self) -> &'static str {
+        match self {
+            Assignment::RateMonotonic => "Rate Monotonic",
+            Assignment::DeadlineMonotonic => "Deadline Monotonic",
+        }
+    }
+}
+
+/// Preemptive fixed-priority scheduling of the jobs of periodic tasks. Every job
+/// gets the priority of its task by the [`Assignment`], and the ready job with the
+/// highest priority runs its whole CPU burst, ties are broken first come first
+/// serve and processes without a period or deadline go last. A job released with a
+/// higher priority than the running one takes the CPU from it.
+///
+/// Invariant of this scheduler is that the ready queue is always sorted by
+/// priority.
+pub struct FixedPriority {
+    ready_queue: VecDeque<Process>,
+    assignment: Assignment,
+}
+
+impl FixedPriority {
+    /// New scheduler with empty ready queue(s), ranking tasks by `assignment`.
+    pub fn new(assignment: Assignment) -> FixedPriority {
+        FixedPriority {
+            ready_queue: VecDeque::new(),
+            assignment,
+        }
+    }
+
+    /// New Rate Monotonic scheduler with empty ready queue(s).
+    pub fn rate_monotonic() -> FixedPriority {
+        FixedPriority::new(Assignment::RateMonotonic)
+    }
+
+    /// New Deadline Monotonic scheduler with empty ready queue(s).
+    pub fn deadline_monotonic() -> FixedPriority {
+        FixedPriority::new(Assignment::DeadlineMonotonic)
+    }
+
+    // Priorities in order, processes without a period or deadline last.
+    fn rank(&self, process: &Process) -> (bool, i32) {
+        let key = match self.assignment {
+            Assignment::RateMonotonic => process.params.period,
+            Assignment::DeadlineMonotonic => process.relative_deadline(),
+        };
+        match key {
+            Some(key) => (false, key),
+            None => (true, 0),
+        }
+    }
+}
+
+impl Scheduler for FixedPriority {
+    fn name(&self) -> &str {
+        self.assignment.label()
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        // Behind every process of the same priority or higher, to maintain invariant.
+        let index = self
+            .ready_queue
+            .iter()
+            .position(|other| self.rank(other) > self.rank(&process))
+            .unwrap_or(self.ready_queue.len());
+        self.ready_queue.insert(index, process);
+    }
+
+    fn pick_next(&mut self, _ctx: &Context) -> Option<(Process, i32)> {
+        let process = self.ready_queue.pop_front()?;
+
+        // Time-quanta is the whole of the current burst, unless preempted. A process
+        // without one fails when the engine runs it.
+        let process_quanta = process.current_burst().unwrap_or(0);
+        Some((process, process_quanta))
+    }
+
+    fn preempts(&self, running: &Process, _remaining: i32, ready: &Process) -> bool {
+        // Ties keep the running process on the CPU.
+        self.rank(ready) < self.rank(running)
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        // Cannot happen as the whole burst is granted, put the process back in line.
+        self.on_ready(process, ctx);
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Priority", self.ready_queue.iter().collect())]
+    }
+}
@@ -0,0 +1,112 @@
+use super::{Context, Scheduler};
+use crate::process::Process;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+/// Non-preemptive Highest Response Ratio Next. The ready process with the highest
+/// response ratio, (wait + burst) / burst, runs its whole CPU burst, ties are broken
+/// first come first serve.
+///
+/// Short bursts still go first, but the ratio of a long burst grows the longer it
+/// waits in the ready queue (see [`Process::ready_since`]), so it cannot starve
+/// behind short bursts that keep returning from IO as with Shortest Job First.
+#[derive(Default)]
+pub struct Hrrn {
+    ready_queue: VecDeque<Process>,
+}
+
+impl Hrrn {
+    /// New scheduler with empty ready queue(s).
+    pub fn new() -> Hrrn {
+        Default::default()
+    }
+
+    fn compare(a: &Process, b: &Process, global_clock: i32) -> Ordering {
+        /*
+            Order of the response ratios of `a` and `b`, cross-multiplied to stay in
+            integers.
+        */
+        let terms = |process: &Process| {
+            let wait = process
+                .ready_since
+                .map_or(0, |ready_since| global_clock - ready_since) as i64;
+            let burst = process.current_burst().unwrap_or(0).max(1) as i64;
+            (wait + burst, burst)
+        };
+        let (a_response, a_burst) = terms(a);
+        let (b_response, b_burst) = terms(b);
+        (a_response * b_burst).cmp(&(b_response * a_burst))
+    }
+}
+
+impl Scheduler for Hrrn {
+    fn name(&self) -> &str {
+        "Highest Response Ratio Next"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        // Kept in the order processes became ready, which breaks ties.
+        self.ready_queue.push_back(process);
+    }
+
+    fn pick_next(&mut self, ctx: &Context) -> Option<(Process, i32)> {
+        // Highest ratio first, the first of equal ones.
+        let (index, _) = self
+            .ready_queue
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| Hrrn::compare(b, a, ctx.global_clock()))?;
+        let process = self.ready_queue.remove(index)?;
+
+        // Time-quanta is the whole of the current burst. A process without one
+        // fails when the engine runs it.
+        let process_quanta = process.current_burst().unwrap_or(0);
+        Some((process, process_quanta))
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        // Cannot happen as the whole burst is granted, put the process back in line.
+        self.on_ready(process, ctx);
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Response Ratio", self.ready_queue.iter().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::simulate;
+    use crate::result::SimulationResult;
+    use crate::scheduler::Sjf;
+
+    #[test]
+    fn does_not_starve_long_bursts() {
+        // Two short processes keep returning from IO before the other one is done.
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("S1", Bursts::alternating(2, &[(1, 2); 30])),
+                Process::new("S2", Bursts::alternating(2, &[(1, 2); 30])),
+                Process::new("L", Bursts::cpu(50)),
+            ])
+        };
+
+        let long = |result: &SimulationResult| {
+            result
+                .metrics
+                .iter()
+                .find(|metrics| metrics.name == "L")
+                .unwrap()
+                .longest_wait
+        };
+        let sjf = simulate(&mut Sjf::new(), workload()).unwrap();
+        assert!(long(&sjf) > 100, "{}", long(&sjf));
+
+        // L catches up with a ratio of 1.5 after waiting 25.
+        let hrrn = simulate(&mut Hrrn::new(), workload()).unwrap();
+        assert!(long(&hrrn) <= 28, "{}", long(&hrrn));
+        assert_eq!(hrrn.global_clock, sjf.global_clock);
+    }
+}
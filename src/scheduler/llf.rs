@@ -0,0 +1,238 @@
+use super::{Context, Migration, Scheduler};
+use crate::process::Process;
+use std::collections::VecDeque;
+
+/// Preemptive Least Laxity First. The laxity of a process is the time it can still
+/// wait and make the deadline of its CPU burst (see [`Process::current_deadline`]):
+/// its deadline, less the global clock, less what is left of the burst. The ready
+/// process with the least laxity runs, ties are broken first come first serve and
+/// processes without a deadline go last.
+///
+/// The laxity of the running process stays the same while that of the waiting ones
+/// shrinks, so laxities are compared again at every scheduling point: whenever a
+/// process arrives or returns from IO, and as soon as a waiting process gets ahead
+/// of the running one, which ends the time slice of the running process.
+///
+/// Processes with about the same laxity would keep taking turns. With a
+/// tie-break threshold the running process keeps the CPU until another one has a
+/// laxity that much lower than its own, and the process that ran last is picked
+/// again over those within the threshold of the least laxity.
+#[derive(Default)]
+pub struct Llf {
+    ready_queue: VecDeque<Process>,
+    threshold: i32,
+    // Name of the process picked last.
+    last_picked: Option<String>,
+}
+
+impl Llf {
+    /// New scheduler with empty ready queue(s), switching processes as soon as one
+    /// has less laxity than another.
+    pub fn new() -> Llf {
+        Default::default()
+    }
+
+    /// Keep the running process on the CPU until another one has a laxity more
+    /// than `threshold` time units lower.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is negative.
+    pub fn with_threshold(mut self, threshold: i32) -> Llf {
+        assert!(threshold >= 0, "tie-break threshold must not be negative");
+        self.threshold = threshold;
+        self
+    }
+
+    fn time_to_pass(&self, running: Option<i64>, waiting: Option<i64>) -> Option<i64> {
+        /*
+            Time units until a process with slack `waiting` is more than the
+            threshold ahead of the running one with `running`, as the laxity of the
+            waiting one shrinks and that of the running one does not. Not positive
+            if it already is, None if it never will be.
+        */
+        match (running, waiting) {
+            (Some(running), Some(waiting)) => Some(waiting - running + self.threshold as i64 + 1),
+            (None, Some(_)) => Some(0),
+            (_, None) => None,
+        }
+    }
+}
+
+// Laxity of the process plus the global clock, with `remaining` time units left of
+// its CPU burst, which orders processes by laxity at any time. None without a
+// deadline.
+fn slack(process: &Process, remaining: i32) -> Option<i64> {
+    process
+        .current_deadline()
+        .map(|deadline| deadline as i64 - remaining as i64)
+}
+
+// Slack of a process that is not running.
+fn waiting_slack(process: &Process) -> Option<i64> {
+    slack(process, process.current_burst().unwrap_or(0))
+}
+
+// Laxities in order, no deadline last.
+fn rank(slack: Option<i64>) -> (bool, i64) {
+    match slack {
+        Some(slack) => (false, slack),
+        None => (true, 0),
+    }
+}
+
+impl Scheduler for Llf {
+    fn name(&self) -> &str {
+        "Least Laxity First"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        // Kept in the order processes became ready, which breaks ties.
+        self.ready_queue.push_back(process);
+    }
+
+    fn pick_next(&mut self, _ctx: &Context) -> Option<(Process, i32)> {
+        // Least laxity first, the process picked last if it is within the threshold.
+        let (least, _) = self
+            .ready_queue
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, process)| rank(waiting_slack(process)))?;
+        let least_rank = rank(waiting_slack(&self.ready_queue[least]));
+        let last = self.ready_queue.iter().position(|process| {
+            Some(&process.name) == self.last_picked.as_ref()
+                && rank(waiting_slack(process))
+                    <= (least_rank.0, least_rank.1 + self.threshold as i64)
+        });
+        let process = self.ready_queue.remove(last.unwrap_or(least))?;
+        self.last_picked = Some(process.name.clone());
+
+        // Time-quanta is the whole of the current burst, or until a waiting process
+        // gets ahead. A process without a burst fails when the engine runs it.
+        let burst = process.current_burst().unwrap_or(0);
+        let running = waiting_slack(&process);
+        let process_quanta = self
+            .ready_queue
+            .iter()
+            .filter_map(|waiting| self.time_to_pass(running, waiting_slack(waiting)))
+            .map(|time| time.clamp(1, i32::MAX as i64) as i32)
+            .fold(burst, i32::min);
+        Some((process, process_quanta))
+    }
+
+    fn preempts(&self, running: &Process, remaining: i32, ready: &Process) -> bool {
+        // Ahead of the running process by more than the threshold already.
+        self.time_to_pass(slack(running, remaining), waiting_slack(ready))
+            .is_some_and(|time| time <= 0)
+    }
+
+    fn cut_slice(&self, running: &Process, remaining: i32, ready: &Process) -> Option<i32> {
+        // Not ahead yet, but it will be.
+        self.time_to_pass(slack(running, remaining), waiting_slack(ready))
+            .map(|time| time.clamp(1, i32::MAX as i64) as i32)
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        // A waiting process got ahead, or is about to, back in line.
+        self.on_ready(process, ctx);
+    }
+
+    fn take_migrating(&mut self, ctx: &Context) -> Option<(Process, Migration)> {
+        // The process that ran last here is still the one to favour.
+        let last_picked = self.last_picked.clone();
+        let (process, _) = self.pick_next(ctx)?;
+        self.last_picked = last_picked;
+        Some((process, Migration::default()))
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Laxity", self.ready_queue.iter().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::simulate;
+    use crate::process::SchedulingParams;
+    use crate::scheduler::Edf;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn switches_as_laxities_cross() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("A", Bursts::cpu(6)).with_params(SchedulingParams {
+                    deadline: Some(12),
+                    ..Default::default()
+                }),
+                Process::new("B", Bursts::cpu(6)).with_params(SchedulingParams {
+                    deadline: Some(12),
+                    ..Default::default()
+                }),
+            ])
+        };
+        let switches = |scheduler: &mut dyn Scheduler| {
+            let result = simulate(scheduler, workload()).unwrap();
+            assert_eq!(result.deadline_misses(), 0, "{}", result.scheduler);
+            result.context_switches
+        };
+
+        // A 0-1, B 1-3, A 3-5, B 5-7, A 7-9, B 9-11, A 11-12: whoever waits has
+        // the least laxity after a unit or two.
+        assert_eq!(switches(&mut Llf::new()), 7);
+        // A 0-3, B 3-9, A 9-12.
+        assert_eq!(switches(&mut Llf::new().with_threshold(2)), 3);
+        assert_eq!(switches(&mut Edf::new()), 2);
+    }
+
+    #[test]
+    fn cuts_the_slice_when_a_waiting_process_gets_ahead() {
+        let processes = VecDeque::from(vec![
+            Process::new("R", Bursts::cpu(10)).with_params(SchedulingParams {
+                deadline: Some(20),
+                ..Default::default()
+            }),
+            Process::new("S", Bursts::cpu(2))
+                .with_params(SchedulingParams {
+                    deadline: Some(15),
+                    ..Default::default()
+                })
+                .with_arrival_time(2),
+        ]);
+
+        // At 2 the laxity of R is 10 and of S 11, by 4 S has 9 against 10. R 0-4,
+        // S 4-6, R 6-12.
+        let result = simulate(&mut Llf::new(), processes).unwrap();
+        assert_eq!(result.preemptions, 0);
+        assert_eq!(result.context_switches, 3);
+        assert_eq!(result.metrics[1].response_time, 2);
+        assert_eq!(result.global_clock, 12);
+    }
+
+    #[test]
+    fn taking_a_process_to_migrate_keeps_the_last_pick() {
+        let io_queue = BTreeMap::new();
+        let emitted = RefCell::new(Vec::new());
+        let ctx = Context::new(0, &io_queue, &emitted);
+
+        // Least Laxity First keeps to the process picked before within the
+        // threshold, here P1 over P3.
+        let job = |name: &str, deadline: i32| {
+            Process::new(name, Bursts::cpu(5)).with_params(SchedulingParams {
+                deadline: Some(deadline),
+                ..Default::default()
+            })
+        };
+        let mut llf = Llf::new().with_threshold(100);
+        llf.on_ready(job("P1", 10), &ctx);
+        llf.on_ready(job("P2", 12), &ctx);
+        let (p1, _) = llf.pick_next(&ctx).unwrap();
+        llf.take_migrating(&ctx).unwrap();
+        llf.on_quantum_expired(p1, &ctx);
+        llf.on_ready(job("P3", 9), &ctx);
+        assert_eq!(llf.pick_next(&ctx).unwrap().0.name, "P1");
+    }
+}
@@ -0,0 +1,305 @@
+use super::{Context, Migration, Scheduler};
+use crate::process::Process;
+use crate::rng::Rng;
+use std::collections::{HashMap, VecDeque};
+
+/// Lottery scheduling, every time-quanta goes to a ready process drawn at random
+/// with a chance proportional to its tickets, see
+/// [`SchedulingParams::tickets`](crate::SchedulingParams::tickets).
+///
+/// Processes of a group (see [`SchedulingParams::group`](crate::SchedulingParams::group))
+/// with a currency hold tickets in that currency: the group as a whole is worth the
+/// currency's base tickets, shared among its ready processes by their tickets.
+///
+/// A process can transfer its tickets to another one while it waits on IO, e.g. a
+/// client waiting on a server. Transferred tickets count as tickets of the
+/// receiving process, in its own currency.
+///
+/// Draws come from a generator seeded at construction, so the same seed gives the
+/// same schedule.
+pub struct Lottery {
+    ready_queue: VecDeque<Process>,
+    time_quanta: i32,
+    rng: Rng,
+    // Base tickets of every currency, by group.
+    currencies: HashMap<String, u32>,
+    // Process the tickets of a process go to while it waits on IO.
+    transfers: HashMap<String, String>,
+}
+
+impl Lottery {
+    /// Time-quanta of every draw unless set otherwise.
+    pub const DEFAULT_QUANTA: i32 = 5;
+    /// Seed of the Lottery entry of the main menu.
+    pub const DEFAULT_SEED: u64 = 42;
+
+    /// New scheduler with empty ready queue(s), drawing with `seed`.
+    pub fn new(seed: u64) -> Lottery {
+        Lottery {
+            ready_queue: VecDeque::new(),
+            time_quanta: Lottery::DEFAULT_QUANTA,
+            rng: Rng::new(seed),
+            currencies: HashMap::new(),
+            transfers: HashMap::new(),
+        }
+    }
+
+    /// Give the winner of a draw `time_quanta` units at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time_quanta` is not positive.
+    pub fn with_time_quanta(mut self, time_quanta: i32) -> Lottery {
+        assert!(time_quanta > 0, "time-quanta must be positive");
+        self.time_quanta = time_quanta;
+        self
+    }
+
+    /// Back the tickets of the processes of `group` with `base_tickets`.
+    pub fn with_currency(mut self, group: &str, base_tickets: u32) -> Lottery {
+        self.currencies.insert(group.to_string(), base_tickets);
+        self
+    }
+
+    /// Hand the tickets of process `from` to process `to` while `from` waits on IO.
+    pub fn with_transfer(mut self, from: &str, to: &str) -> Lottery {
+        self.transfers.insert(from.to_string(), to.to_string());
+        self
+    }
+
+    fn values(&self, ctx: &Context) -> Vec<f64> {
+        /*
+            Base tickets of every ready process, in ready queue order.
+        */
+        let tickets: Vec<u32> = self
+            .ready_queue
+            .iter()
+            .map(|process| {
+                let transferred: u32 = ctx
+                    .io_queue()
+                    .filter(|from| self.transfers.get(&from.name) == Some(&process.name))
+                    .map(|from| from.params.tickets)
+                    .sum();
+                process.params.tickets + transferred
+            })
+            .collect();
+
+        // Tickets issued in every currency to ready processes.
+        let mut issued: HashMap<&str, u32> = HashMap::new();
+        for (process, &tickets) in self.ready_queue.iter().zip(tickets.iter()) {
+            if let Some(group) = self.currency_of(process) {
+                *issued.entry(group).or_insert(0) += tickets;
+            }
+        }
+
+        self.ready_queue
+            .iter()
+            .zip(tickets.iter())
+            .map(|(process, &tickets)| self.base_tickets(process, tickets, &issued))
+            .collect()
+    }
+
+    fn base_tickets(&self, process: &Process, tickets: u32, issued: &HashMap<&str, u32>) -> f64 {
+        /*
+            Worth of `tickets` of `process` in base tickets, given the tickets issued
+            in every currency.
+        */
+        match self.currency_of(process) {
+            Some(group) if issued[group] > 0 => {
+                self.currencies[group] as f64 * tickets as f64 / issued[group] as f64
+            }
+            Some(_) => 0.0,
+            None => tickets as f64,
+        }
+    }
+
+    fn currency_of<'p>(&self, process: &'p Process) -> Option<&'p str> {
+        process
+            .params
+            .group
+            .as_deref()
+            .filter(|group| self.currencies.contains_key(*group))
+    }
+}
+
+impl Default for Lottery {
+    fn default() -> Lottery {
+        Lottery::new(Lottery::DEFAULT_SEED)
+    }
+}
+
+impl Scheduler for Lottery {
+    fn name(&self) -> &str {
+        "Lottery"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        self.ready_queue.push_back(process);
+    }
+
+    fn pick_next(&mut self, ctx: &Context) -> Option<(Process, i32)> {
+        if self.ready_queue.is_empty() {
+            return None;
+        }
+
+        // Walk the ready queue until the winning ticket. Without any tickets in
+        // play, the process that has been ready the longest wins.
+        let values = self.values(ctx);
+        let total: f64 = values.iter().sum();
+        let mut winner = 0;
+        if total > 0.0 {
+            let mut ticket = self.rng.next_f64() * total;
+            winner = values.len() - 1;
+            for (index, value) in values.iter().enumerate() {
+                if ticket < *value {
+                    winner = index;
+                    break;
+                }
+                ticket -= value;
+            }
+        }
+
+        let process = self.ready_queue.remove(winner)?;
+        Some((process, self.time_quanta))
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        self.on_ready(process, ctx);
+    }
+
+    fn take_migrating(&mut self, ctx: &Context) -> Option<(Process, Migration)> {
+        // The draw does not count, the next one here comes out the same.
+        let rng = self.rng.clone();
+        let (process, _) = self.pick_next(ctx)?;
+        self.rng = rng;
+        Some((process, Migration::default()))
+    }
+
+    fn share_weights(&self, processes: &[Process]) -> Option<Vec<f64>> {
+        // Tickets issued in every currency, to all processes.
+        let mut issued: HashMap<&str, u32> = HashMap::new();
+        for process in processes.iter() {
+            if let Some(group) = self.currency_of(process) {
+                *issued.entry(group).or_insert(0) += process.params.tickets;
+            }
+        }
+
+        Some(
+            processes
+                .iter()
+                .map(|process| self.base_tickets(process, process.params.tickets, &issued))
+                .collect(),
+        )
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Lottery", self.ready_queue.iter().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::simulate;
+    use crate::process::SchedulingParams;
+    use crate::scheduler::Policy;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn shares_follow_tickets() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("P1", Bursts::cpu(2000)).with_params(SchedulingParams {
+                    tickets: 300,
+                    ..Default::default()
+                }),
+                Process::new("P2", Bursts::cpu(2000)),
+                Process::new("P3", Bursts::cpu(2000)).with_params(SchedulingParams {
+                    group: Some("alice".to_string()),
+                    ..Default::default()
+                }),
+                Process::new("P4", Bursts::cpu(2000)).with_params(SchedulingParams {
+                    tickets: 300,
+                    group: Some("alice".to_string()),
+                    ..Default::default()
+                }),
+            ])
+        };
+
+        // Alice's processes share her 200 base tickets, 50 and 150.
+        let result =
+            simulate(&mut Lottery::new(7).with_currency("alice", 200), workload()).unwrap();
+        let entitled: Vec<f32> = result
+            .shares
+            .iter()
+            .map(|share| share.entitled_share)
+            .collect();
+        assert_eq!(entitled, vec![0.5, 1.0 / 6.0, 1.0 / 12.0, 0.25]);
+        for share in result.shares.iter() {
+            assert!((share.cpu_share - share.entitled_share).abs() < 0.05);
+        }
+
+        // The same seed draws the same schedule.
+        let again = simulate(&mut Lottery::new(7).with_currency("alice", 200), workload()).unwrap();
+        assert_eq!(again.shares, result.shares);
+        assert!(Policy::Fcfs.run(workload()).unwrap().shares.is_empty());
+    }
+
+    #[test]
+    fn transfers_tickets_during_io() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("Client", Bursts::cpu(1).io(0, 1000).cpu(1)).with_params(
+                    SchedulingParams {
+                        tickets: 900,
+                        ..Default::default()
+                    },
+                ),
+                Process::new("Server", Bursts::cpu(1000)),
+                Process::new("Other", Bursts::cpu(1000)),
+            ])
+        };
+
+        // The server holds 1000 tickets against 100 while the client waits on it.
+        let result = simulate(
+            &mut Lottery::new(3).with_transfer("Client", "Server"),
+            workload(),
+        )
+        .unwrap();
+        let server = &result.metrics[2];
+        assert_eq!(server.name, "Server");
+        assert!(server.turnaround_time < 1200);
+
+        let result = simulate(&mut Lottery::new(3), workload()).unwrap();
+        assert!(result.metrics[2].turnaround_time > 1800);
+    }
+
+    #[test]
+    fn taking_a_process_to_migrate_does_not_count_the_draw() {
+        let io_queue = BTreeMap::new();
+        let emitted = RefCell::new(Vec::new());
+        let ctx = Context::new(0, &io_queue, &emitted);
+
+        // Lottery draws again for the process that would have won.
+        let mut moved = Lottery::new(7);
+        for name in ["P1", "P2", "P3"] {
+            moved.on_ready(Process::new(name, Bursts::cpu(5)), &ctx);
+        }
+        moved.take_migrating(&ctx).unwrap();
+        let mut kept = Lottery::new(7);
+        for (_, queue) in moved.queues() {
+            for process in queue {
+                kept.on_ready(process.clone(), &ctx);
+            }
+        }
+        for _ in 0..5 {
+            let (a, _) = moved.pick_next(&ctx).unwrap();
+            let (b, _) = kept.pick_next(&ctx).unwrap();
+            assert_eq!(a.name, b.name);
+            moved.on_quantum_expired(a, &ctx);
+            kept.on_quantum_expired(b, &ctx);
+        }
+    }
+}
@@ -0,0 +1,523 @@
+use super::{Context, Migration, Scheduler};
+use crate::observer::SimEvent;
+use crate::process::Process;
+use sorts::quick_sort::quick_sort;
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+
+/// How a level of a [`Mlfq`] orders its processes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelPolicy {
+    /// First in, first out. A process that uses up its time-quanta on the last
+    /// level goes to the back of it.
+    RoundRobin,
+    /// First in, first out. A process that uses up its time-quanta on the last
+    /// level stays at the front of it.
+    Fcfs,
+    /// Shortest current CPU burst first.
+    Sjf,
+}
+
+/// One level of a [`Mlfq`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level {
+    /// How the level orders its processes.
+    pub policy: LevelPolicy,
+    /// Time-quanta of the processes of this level, None to run the whole CPU burst.
+    pub time_quanta: Option<i32>,
+    /// CPU time a process may use at this level before it moves down a level,
+    /// counted across its IO bursts. None to move down once a whole time-quanta is
+    /// used up.
+    ///
+    /// Once any level has an allotment, processes return from IO to the level they
+    /// were at rather than to the first level.
+    pub allotment: Option<i32>,
+}
+
+impl Level {
+    /// Round-Robin level with a time-quanta of `time_quanta`.
+    pub fn round_robin(time_quanta: i32) -> Level {
+        Level {
+            policy: LevelPolicy::RoundRobin,
+            time_quanta: Some(time_quanta),
+            allotment: None,
+        }
+    }
+
+    /// First Come First Serve level that runs the whole CPU burst.
+    pub fn fcfs() -> Level {
+        Level {
+            policy: LevelPolicy::Fcfs,
+            time_quanta: None,
+            allotment: None,
+        }
+    }
+
+    /// Shortest Job First level that runs the whole CPU burst.
+    pub fn sjf() -> Level {
+        Level {
+            policy: LevelPolicy::Sjf,
+            time_quanta: None,
+            allotment: None,
+        }
+    }
+
+    /// Same level, moving processes down once they used `allotment` units of CPU
+    /// time at it.
+    pub fn with_allotment(mut self, allotment: i32) -> Level {
+        self.allotment = Some(allotment);
+        self
+    }
+
+    fn label(&self) -> String {
+        let policy = match self.policy {
+            LevelPolicy::RoundRobin => "RR",
+            LevelPolicy::Fcfs => "FCFS",
+            LevelPolicy::Sjf => "SJF",
+        };
+        match self.time_quanta {
+            Some(time_quanta) => format!("{} {}", policy, time_quanta),
+            None => policy.to_string(),
+        }
+    }
+}
+
+/// Levels and rules of a [`Mlfq`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlfqConfig {
+    /// Levels from highest to lowest priority. Processes enter (and re-enter after
+    /// IO) at the first one, and move down a level when they use up their
+    /// time-quanta.
+    pub levels: Vec<Level>,
+    /// Move every process back to the first level every so many time units.
+    pub boost_interval: Option<i32>,
+    /// A process below the first level keeps its level when its time-quanta runs
+    /// out at the very moment another process returns from IO.
+    pub hold_on_io_return: bool,
+}
+
+impl MlfqConfig {
+    /// The scheduler of the original assignment: Round-Robin with a time-quanta of
+    /// 5, Round-Robin with a time-quanta of 10, then Shortest Job First. A process
+    /// on the second level is spared from demotion when another process returns
+    /// from IO as its time-quanta runs out. There is no priority boost.
+    pub fn classic() -> MlfqConfig {
+        MlfqConfig {
+            levels: vec![Level::round_robin(5), Level::round_robin(10), Level::sjf()],
+            boost_interval: None,
+            hold_on_io_return: true,
+        }
+    }
+
+    /// The scheduler as the README describes it: Round-Robin with a time-quanta of
+    /// 5, Round-Robin with a time-quanta of 10, then First Come First Serve, and
+    /// every process that uses up its time-quanta moves down a level.
+    pub fn fcfs_last_level() -> MlfqConfig {
+        MlfqConfig {
+            levels: vec![Level::round_robin(5), Level::round_robin(10), Level::fcfs()],
+            boost_interval: None,
+            hold_on_io_return: false,
+        }
+    }
+
+    /// [`MlfqConfig::fcfs_last_level`] with time allotment accounting: a process
+    /// moves down from the Round-Robin levels once it used two time-quanta of CPU
+    /// time there, however often it gave up the CPU for IO in between.
+    pub fn allotted() -> MlfqConfig {
+        MlfqConfig {
+            levels: vec![
+                Level::round_robin(5).with_allotment(10),
+                Level::round_robin(10).with_allotment(20),
+                Level::fcfs(),
+            ],
+            boost_interval: None,
+            hold_on_io_return: false,
+        }
+    }
+
+    /// Same config with every process moved back to the first level every
+    /// `interval` time units.
+    pub fn with_boost(mut self, interval: i32) -> MlfqConfig {
+        self.boost_interval = Some(interval);
+        self
+    }
+}
+
+impl Default for MlfqConfig {
+    fn default() -> MlfqConfig {
+        MlfqConfig::classic()
+    }
+}
+
+/// Multi-Level Feedback Queue, a list of levels that each run their processes by
+/// their own policy and time-quanta, see [`MlfqConfig`]. The first level with a
+/// process ready gets the CPU.
+///
+/// Invariant of this scheduler is that Shortest Job First levels are always
+/// sorted. A level is sorted whenever a process is inserted (it starts out empty).
+pub struct Mlfq {
+    config: MlfqConfig,
+    labels: Vec<String>,
+    levels: Vec<VecDeque<Process>>,
+    // Processes dispatched and not back yet, by name.
+    running: HashMap<String, Running>,
+    // Global clock of the next priority boost, if any.
+    next_boost: Option<i32>,
+    // Level and CPU time used at it of processes on levels with an allotment.
+    accounts: HashMap<String, Account>,
+}
+
+#[derive(Default)]
+struct Running {
+    // Level the process was taken from, 0 being the first one.
+    level: usize,
+    // CPU time the process was going to use.
+    slice: i32,
+}
+
+#[derive(Default)]
+struct Account {
+    level: usize,
+    used: i32,
+}
+
+// What a process moved to another run queue takes along.
+struct Migrating {
+    // Level the process was taken from.
+    level: usize,
+    account: Option<Account>,
+}
+
+impl Mlfq {
+    /// New scheduler with empty ready queue(s), set up as [`MlfqConfig::classic`].
+    pub fn new() -> Mlfq {
+        Mlfq::with_config(MlfqConfig::classic())
+    }
+
+    /// New scheduler with empty ready queue(s), set up as `config`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` has no levels, or a time-quanta, allotment or boost
+    /// interval that is not positive.
+    pub fn with_config(config: MlfqConfig) -> Mlfq {
+        assert!(!config.levels.is_empty(), "MLFQ needs at least one level");
+        assert!(
+            config
+                .levels
+                .iter()
+                .all(|level| level.time_quanta.is_none_or(|quanta| quanta > 0)),
+            "time-quanta must be positive"
+        );
+        assert!(
+            config
+                .levels
+                .iter()
+                .all(|level| level.allotment.is_none_or(|allotment| allotment > 0)),
+            "allotment must be positive"
+        );
+        assert!(
+            config.boost_interval.is_none_or(|interval| interval > 0),
+            "boost interval must be positive"
+        );
+
+        Mlfq {
+            labels: config
+                .levels
+                .iter()
+                .enumerate()
+                .map(|(index, level)| format!("Level {} {}", index + 1, level.label()))
+                .collect(),
+            levels: config.levels.iter().map(|_| VecDeque::new()).collect(),
+            running: HashMap::new(),
+            next_boost: config.boost_interval,
+            accounts: HashMap::new(),
+            config,
+        }
+    }
+
+    fn insert(&mut self, level: usize, process: Process, expired: bool) {
+        /*
+            Put the process in the queue of `level` according to the policy of the
+            level, `expired` when it used up its time-quanta on that same level.
+        */
+        let queue = &mut self.levels[level];
+        match self.config.levels[level].policy {
+            LevelPolicy::Fcfs if expired => queue.push_front(process),
+            LevelPolicy::RoundRobin | LevelPolicy::Fcfs => queue.push_back(process),
+            LevelPolicy::Sjf => {
+                queue.push_back(process);
+                quick_sort(queue.make_contiguous());
+            }
+        }
+    }
+
+    fn charge(&mut self, name: &str, level: usize, slice: i32) -> Option<bool> {
+        /*
+            Add the time slice to the CPU time the process used at `level`.
+            None if the level has no allotment, otherwise whether the process used
+            it up, which starts the count over.
+        */
+        let allotment = self.config.levels[level].allotment?;
+        let account = self.accounts.entry(name.to_string()).or_default();
+        account.used += slice;
+        let used_up = account.used >= allotment;
+        if used_up {
+            account.used = 0;
+        }
+        Some(used_up)
+    }
+
+    fn boost(&mut self, ctx: &Context) {
+        /*
+            Move every process back to the first level once the boost is due. The
+            running processes count as taken from the first level.
+        */
+        let (interval, next_boost) = match (self.config.boost_interval, self.next_boost) {
+            (Some(interval), Some(next_boost)) if next_boost <= ctx.global_clock() => {
+                (interval, next_boost)
+            }
+            _ => return,
+        };
+        let periods = (ctx.global_clock() - next_boost) / interval + 1;
+        self.next_boost = Some(next_boost + periods * interval);
+
+        for running in self.running.values_mut() {
+            running.level = 0;
+        }
+        self.accounts.clear();
+        for level in 1..self.levels.len() {
+            for process in std::mem::take(&mut self.levels[level]) {
+                ctx.emit(SimEvent::Promote {
+                    time: ctx.global_clock(),
+                    process: process.name.clone(),
+                    from: level,
+                    to: 0,
+                });
+                self.insert(0, process, false);
+            }
+        }
+    }
+}
+
+impl Default for Mlfq {
+    fn default() -> Mlfq {
+        Mlfq::new()
+    }
+}
+
+impl Scheduler for Mlfq {
+    fn name(&self) -> &str {
+        "Multi-Level Feedback Queue"
+    }
+
+    fn on_ready(&mut self, process: Process, ctx: &Context) {
+        // All processes enter at the first level, and re-enter after IO at the first
+        // level unless they were on a level with an allotment.
+        self.boost(ctx);
+        let level = self
+            .accounts
+            .get(&process.name)
+            .map_or(0, |account| account.level);
+        self.insert(level, process, false);
+    }
+
+    fn pick_next(&mut self, ctx: &Context) -> Option<(Process, i32)> {
+        // Checking levels by priority, the first one with a process ready runs it.
+        self.boost(ctx);
+        let level = self.levels.iter().position(|queue| !queue.is_empty())?;
+        let process = self.levels[level].pop_front()?;
+
+        // A process without a CPU burst fails when the engine runs it.
+        let burst = process.current_burst().unwrap_or(0);
+        let process_quanta = self.config.levels[level].time_quanta.unwrap_or(burst);
+        self.running.insert(
+            process.name.clone(),
+            Running {
+                level,
+                slice: process_quanta.min(burst),
+            },
+        );
+        Some((process, process_quanta))
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        self.boost(ctx);
+        let Running { level, slice } = self.running.remove(&process.name).unwrap_or_default();
+        let last = level + 1 == self.levels.len();
+        let used_up = if last {
+            None
+        } else {
+            self.charge(&process.name, level, slice)
+        };
+
+        // Peek top of IO queue to check for any processes that will enter the first
+        // level at the end of this step.
+        let io_returning = || {
+            ctx.io_queue()
+                .min_by(|a, b| self.io_return_order(a, b))
+                .is_some_and(|io_process| io_process.return_from_io_time <= ctx.global_clock())
+        };
+
+        // The last level keeps its processes. Otherwise a process moves down once it
+        // used up its allotment, or its time-quanta on levels without one unless it
+        // is spared.
+        let to = match used_up {
+            _ if last => level,
+            Some(true) => level + 1,
+            Some(false) => level,
+            None if level > 0 && self.config.hold_on_io_return && io_returning() => level,
+            None => level + 1,
+        };
+
+        if to != level {
+            ctx.emit(SimEvent::Demote {
+                time: ctx.global_clock(),
+                process: process.name.clone(),
+                from: level,
+                to,
+            });
+            self.insert(to, process, false);
+        } else {
+            self.insert(to, process, true);
+        }
+    }
+
+    fn on_burst_complete(&mut self, process: &Process, ctx: &Context) {
+        // Remember the level to return to after IO, when allotments are in use.
+        self.boost(ctx);
+        let Running { level, slice } = self.running.remove(&process.name).unwrap_or_default();
+        let to = match self.charge(&process.name, level, slice) {
+            Some(true) if level + 1 < self.levels.len() => level + 1,
+            Some(_) => level,
+            None if self
+                .config
+                .levels
+                .iter()
+                .any(|level| level.allotment.is_some()) =>
+            {
+                level
+            }
+            None => return,
+        };
+
+        if to != level {
+            ctx.emit(SimEvent::Demote {
+                time: ctx.global_clock(),
+                process: process.name.clone(),
+                from: level,
+                to,
+            });
+        }
+        self.accounts.entry(process.name.clone()).or_default().level = to;
+    }
+
+    fn take_migrating(&mut self, ctx: &Context) -> Option<(Process, Migration)> {
+        // A boost that is due happens first, so the level taken along is current.
+        self.boost(ctx);
+        let level = self.levels.iter().position(|queue| !queue.is_empty())?;
+        let process = self.levels[level].pop_front()?;
+        let account = self.accounts.remove(&process.name);
+        Some((process, Migration::new(Migrating { level, account })))
+    }
+
+    fn admit_migrated(&mut self, process: Process, migration: Migration, ctx: &Context) {
+        // The process keeps its level, and what it used of the allotment there.
+        self.boost(ctx);
+        let Migrating { level, account } = match migration.into_state() {
+            Some(migrating) => migrating,
+            None => return self.on_ready(process, ctx),
+        };
+        if let Some(account) = account {
+            self.accounts.insert(process.name.clone(), account);
+        }
+        self.insert(level.min(self.levels.len() - 1), process, false);
+    }
+
+    fn io_return_order(&self, a: &Process, b: &Process) -> Ordering {
+        // The IO queue is kept sorted by the next CPU burst, so processes returning
+        // together enter the first level shortest burst first.
+        a.partial_cmp(b).unwrap_or(Ordering::Equal)
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        self.labels
+            .iter()
+            .zip(self.levels.iter())
+            .map(|(label, queue)| (label.as_str(), queue.iter().collect()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::{simulate_with, SimulationConfig};
+    use crate::observer::EventLog;
+    use crate::scheduler::Policy;
+    use crate::workload::gaming_workload;
+
+    #[test]
+    fn presets_differ_on_last_level() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("P1", Bursts::cpu(20)),
+                Process::new("P2", Bursts::cpu(16)),
+            ])
+        };
+
+        // Both reach the last level at 30 with P1 5 units and P2 1 unit left.
+        let classic = Policy::Mlfq.run(workload()).unwrap();
+        assert_eq!(classic.metrics[0].turnaround_time, 35);
+        assert_eq!(classic.metrics[1].turnaround_time, 30);
+
+        let fcfs = Policy::MlfqFcfs.run(workload()).unwrap();
+        assert_eq!(fcfs.metrics[0].turnaround_time, 34);
+        assert_eq!(fcfs.metrics[1].turnaround_time, 35);
+    }
+
+    #[test]
+    fn allotment_stops_gaming() {
+        // The gamers keep the first level to themselves until they are done.
+        let gamed = Policy::MlfqFcfs.run(gaming_workload()).unwrap();
+        assert_eq!(gamed.metrics[0].turnaround_time, 173);
+        assert_eq!(gamed.metrics[2].longest_wait, 165);
+
+        // They move down after 10 units of CPU time at the first level.
+        let allotted = Policy::MlfqAllotted.run(gaming_workload()).unwrap();
+        assert_eq!(allotted.global_clock, gamed.global_clock);
+        assert_eq!(allotted.metrics[0].turnaround_time, 283);
+        assert_eq!(allotted.metrics[2].longest_wait, 46);
+    }
+
+    #[test]
+    fn boost_promotes_waiting_processes() {
+        let processes = VecDeque::from(vec![
+            Process::new("P1", Bursts::cpu(12)),
+            Process::new("P2", Bursts::cpu(12)),
+        ]);
+        let config = MlfqConfig {
+            levels: vec![Level::round_robin(2), Level::round_robin(4)],
+            boost_interval: Some(10),
+            hold_on_io_return: false,
+        };
+
+        // P1 0-2, P2 2-4, P1 4-8, P2 8-12 when the boost is due and P1 moves back up.
+        let mut log = EventLog::default();
+        let result = simulate_with(
+            &mut Mlfq::with_config(config),
+            processes,
+            &SimulationConfig::default(),
+            &mut [&mut log],
+        )
+        .unwrap();
+        assert_eq!(result.global_clock, 24);
+        assert!(log.events.contains(&SimEvent::Promote {
+            time: 12,
+            process: "P1".to_string(),
+            from: 1,
+            to: 0
+        }));
+    }
+}
@@ -0,0 +1,353 @@
+//! The [`Scheduler`] trait and the built-in scheduling policies.
+
+mod cfs;
+mod edf;
+mod eevdf;
+mod fcfs;
+mod fixed_priority;
+mod hrrn;
+mod llf;
+mod lottery;
+mod mlfq;
+mod per_cpu;
+mod priority;
+mod round_robin;
+mod sjf;
+mod srtf;
+mod stride;
+
+pub use cfs::Cfs;
+pub use edf::Edf;
+pub use eevdf::Eevdf;
+pub use fcfs::Fcfs;
+pub use fixed_priority::{Assignment, FixedPriority};
+pub use hrrn::Hrrn;
+pub use llf::Llf;
+pub use lottery::Lottery;
+pub use mlfq::{Level, LevelPolicy, Mlfq, MlfqConfig};
+pub use per_cpu::PerCpu;
+pub use priority::Priority;
+pub use round_robin::RoundRobin;
+pub use sjf::Sjf;
+pub use srtf::Srtf;
+pub use stride::Stride;
+
+use crate::engine::{simulate_with, SimulationConfig};
+use crate::error::SimError;
+use crate::observer::{Observer, SimEvent};
+use crate::process::Process;
+use crate::result::SimulationResult;
+use std::any::Any;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Read-only view of the simulation handed to every scheduler hook.
+pub struct Context<'a> {
+    global_clock: i32,
+    cpu: Option<usize>,
+    io_queue: &'a BTreeMap<u64, Process>,
+    emitted: &'a RefCell<Vec<SimEvent>>,
+}
+
+impl<'a> Context<'a> {
+    pub(crate) fn new(
+        global_clock: i32,
+        io_queue: &'a BTreeMap<u64, Process>,
+        emitted: &'a RefCell<Vec<SimEvent>>,
+    ) -> Context<'a> {
+        Context {
+            global_clock,
+            cpu: None,
+            io_queue,
+            emitted,
+        }
+    }
+
+    pub(crate) fn on_cpu(mut self, cpu: usize) -> Context<'a> {
+        self.cpu = Some(cpu);
+        self
+    }
+
+    /// Current value of the global clock.
+    pub fn global_clock(&self) -> i32 {
+        self.global_clock
+    }
+
+    /// CPU a process is picked for, from 0. Only set for [`Scheduler::pick_next`].
+    pub fn cpu(&self) -> Option<usize> {
+        self.cpu
+    }
+
+    /// Processes currently waiting on IO, in the order they started their IO burst.
+    pub fn io_queue(&self) -> impl Iterator<Item = &Process> {
+        self.io_queue.values()
+    }
+
+    /// Report a scheduler specific event, e.g. a demotion, to the observers.
+    pub fn emit(&self, event: SimEvent) {
+        self.emitted.borrow_mut().push(event);
+    }
+}
+
+/// What a scheduler keeps about a process it moves to another run queue, e.g. its
+/// place in line, see [`Scheduler::take_migrating`]. Only a scheduler of the same kind
+/// reads it.
+#[derive(Default)]
+pub struct Migration(Option<Box<dyn Any>>);
+
+impl Migration {
+    /// Migration carrying `state`.
+    pub fn new<T: Any>(state: T) -> Migration {
+        Migration(Some(Box::new(state)))
+    }
+
+    /// The state carried, None if there is none or it is not a `T`.
+    pub fn into_state<T: Any>(self) -> Option<T> {
+        self.0
+            .and_then(|state| state.downcast().ok())
+            .map(|state| *state)
+    }
+}
+
+/// What happens to a CPU burst still running, or waiting to run, when its
+/// deadline passes, see [`Process::current_deadline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overrun {
+    /// The burst runs to completion, late.
+    #[default]
+    Continue,
+    /// The process is dropped along with every burst it has left.
+    AbortJob,
+    /// The rest of the burst is dropped and the process moves on to its next
+    /// burst. Aborts the job when the deadline is that of the whole job.
+    SkipBurst,
+}
+
+/// A scheduling policy. The policy owns its ready queue(s) and decides who runs next
+/// and for how long, the engine in `crate::engine` owns the clock, the IO queue and
+/// the finished processes.
+///
+/// On a machine with more than one CPU a process is picked for every idle CPU, so
+/// several picked processes can be running at once and come back in any order. The
+/// ready queue(s) are shared by all CPUs, unless the scheduler keeps them per CPU,
+/// see [`PerCpu`].
+pub trait Scheduler {
+    /// Name used when reporting the results of a simulation.
+    fn name(&self) -> &str;
+
+    /// A process has entered the ready state, either at the start of the simulation
+    /// or on its return from an IO burst.
+    fn on_ready(&mut self, process: Process, ctx: &Context);
+
+    /// Remove the next process to run from the ready queue(s), along with the
+    /// time-quanta it is allowed to use. Returns None when nothing is ready.
+    fn pick_next(&mut self, ctx: &Context) -> Option<(Process, i32)>;
+
+    /// The process used up its time-quanta before its CPU burst completed.
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context);
+
+    /// Whether `ready`, which just arrived or returned from IO, takes the CPU from
+    /// `running`, which has `remaining` time units of its CPU burst left. Never
+    /// for non-preemptive schedulers.
+    fn preempts(&self, _running: &Process, _remaining: i32, _ready: &Process) -> bool {
+        false
+    }
+
+    /// Time units from now after which the time slice of `running`, which has
+    /// `remaining` time units of its CPU burst left, ends now that `ready` just
+    /// arrived or returned from IO without preempting it. Its time slice is kept by
+    /// default, and when it would end sooner anyway.
+    fn cut_slice(&self, _running: &Process, _remaining: i32, _ready: &Process) -> Option<i32> {
+        None
+    }
+
+    /// The process was taken off the CPU, see [`Scheduler::preempts`], with the
+    /// rest of its CPU burst left to run. Puts it back in the ready queue(s) by
+    /// default.
+    fn on_preempted(&mut self, process: Process, ctx: &Context) {
+        self.on_ready(process, ctx);
+    }
+
+    /// The process completed its CPU burst, and is about to start IO or exit.
+    fn on_burst_complete(&mut self, _process: &Process, _ctx: &Context) {}
+
+    /// Remove the process `pick_next` would return from the ready queue(s), to move it
+    /// to another run queue rather than run it, see [`PerCpu`]. Picking it has no
+    /// other effect, e.g. a draw, and it comes with what the scheduler keeps about it
+    /// for [`Scheduler::admit_migrated`]. Returns None when nothing is ready.
+    ///
+    /// By default the process is picked by `pick_next`, and carries nothing.
+    fn take_migrating(&mut self, ctx: &Context) -> Option<(Process, Migration)> {
+        self.pick_next(ctx)
+            .map(|(process, _)| (process, Migration::default()))
+    }
+
+    /// A process taken by [`Scheduler::take_migrating`] of another scheduler of the
+    /// same kind enters the ready state, with what that scheduler kept about it. Puts
+    /// it in the ready queue(s) like a new process by default.
+    fn admit_migrated(&mut self, process: Process, _migration: Migration, ctx: &Context) {
+        self.on_ready(process, ctx);
+    }
+
+    /// Order of processes that return from IO during the same step, before they are
+    /// handed to `on_ready`. Ties keep the order the processes started their IO.
+    fn io_return_order(&self, _a: &Process, _b: &Process) -> Ordering {
+        Ordering::Equal
+    }
+
+    /// What happens to late CPU bursts, see [`Overrun`]. They run to completion by
+    /// default.
+    fn overrun(&self) -> Overrun {
+        Overrun::Continue
+    }
+
+    /// Relative share of the CPU every process of `processes` is entitled to, e.g.
+    /// by its tickets, for proportional-share schedulers. None for the others.
+    fn share_weights(&self, _processes: &[Process]) -> Option<Vec<f64>> {
+        None
+    }
+
+    /// Number of ready processes queued for every CPU, for schedulers with ready
+    /// queue(s) per CPU. None when the ready queue(s) are shared.
+    fn cpu_loads(&self) -> Option<Vec<usize>> {
+        None
+    }
+
+    /// Named ready queues, used by observers to show context switch information.
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)>;
+}
+
+/// The built-in scheduling policies, used to pick a scheduler by menu entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Non-preemptive First Come First Serve, see [`Fcfs`].
+    Fcfs,
+    /// Non-preemptive Shortest Job First, see [`Sjf`].
+    Sjf,
+    /// Multi-Level Feedback Queue, see [`MlfqConfig::classic`].
+    Mlfq,
+    /// Multi-Level Feedback Queue with a First Come First Serve last level, see
+    /// [`MlfqConfig::fcfs_last_level`].
+    MlfqFcfs,
+    /// Multi-Level Feedback Queue with time allotment accounting, see
+    /// [`MlfqConfig::allotted`].
+    MlfqAllotted,
+    /// Round-Robin with the default time-quanta, see [`RoundRobin`].
+    RoundRobin,
+    /// Shortest Remaining Time First, see [`Srtf`].
+    Srtf,
+    /// Non-preemptive Priority without aging, see [`Priority::new`].
+    Priority,
+    /// Preemptive Priority without aging, see [`Priority::preemptive`].
+    PriorityPreemptive,
+    /// Lottery with the default seed and time-quanta, see [`Lottery`].
+    Lottery,
+    /// Stride with the default time-quanta, see [`Stride`].
+    Stride,
+    /// Completely Fair Scheduler with the default sched_latency and min_granularity,
+    /// see [`Cfs`].
+    Cfs,
+    /// Earliest Eligible Virtual Deadline First with the default base slice, see
+    /// [`Eevdf`].
+    Eevdf,
+    /// Highest Response Ratio Next, see [`Hrrn`].
+    Hrrn,
+    /// Earliest Deadline First running late work to completion, see [`Edf`].
+    Edf,
+    /// Least Laxity First without a tie-break threshold, see [`Llf`].
+    Llf,
+    /// Rate Monotonic fixed priorities, see [`FixedPriority::rate_monotonic`].
+    RateMonotonic,
+    /// Deadline Monotonic fixed priorities, see
+    /// [`FixedPriority::deadline_monotonic`].
+    DeadlineMonotonic,
+}
+
+impl Policy {
+    /// Every policy, in the order they are listed in the main menu.
+    pub const ALL: [Policy; 18] = [
+        Policy::Fcfs,
+        Policy::Sjf,
+        Policy::Mlfq,
+        Policy::MlfqFcfs,
+        Policy::MlfqAllotted,
+        Policy::RoundRobin,
+        Policy::Srtf,
+        Policy::Priority,
+        Policy::PriorityPreemptive,
+        Policy::Lottery,
+        Policy::Stride,
+        Policy::Cfs,
+        Policy::Eevdf,
+        Policy::Hrrn,
+        Policy::Edf,
+        Policy::Llf,
+        Policy::RateMonotonic,
+        Policy::DeadlineMonotonic,
+    ];
+
+    /// Menu entry for this policy.
+    pub fn label(self) -> &'static str {
+        match self {
+            Policy::Fcfs => "First Come First Serve",
+            Policy::Sjf => "Shortest Job First",
+            Policy::Mlfq => "Multi-Level Feedback Queue",
+            Policy::MlfqFcfs => "Multi-Level Feedback Queue (FCFS last level)",
+            Policy::MlfqAllotted => "Multi-Level Feedback Queue (time allotment)",
+            Policy::RoundRobin => "Round-Robin",
+            Policy::Srtf => "Shortest Remaining Time First",
+            Policy::Priority => "Priority",
+            Policy::PriorityPreemptive => "Priority (preemptive)",
+            Policy::Lottery => "Lottery",
+            Policy::Stride => "Stride",
+            Policy::Cfs => "Completely Fair Scheduler",
+            Policy::Eevdf => "Earliest Eligible Virtual Deadline First",
+            Policy::Hrrn => "Highest Response Ratio Next",
+            Policy::Edf => "Earliest Deadline First",
+            Policy::Llf => "Least Laxity First",
+            Policy::RateMonotonic => "Rate Monotonic",
+            Policy::DeadlineMonotonic => "Deadline Monotonic",
+        }
+    }
+
+    /// New scheduler implementing this policy.
+    pub fn scheduler(self) -> Box<dyn Scheduler> {
+        match self {
+            Policy::Fcfs => Box::new(Fcfs::new()),
+            Policy::Sjf => Box::new(Sjf::new()),
+            Policy::Mlfq => Box::new(Mlfq::new()),
+            Policy::MlfqFcfs => Box::new(Mlfq::with_config(MlfqConfig::fcfs_last_level())),
+            Policy::MlfqAllotted => Box::new(Mlfq::with_config(MlfqConfig::allotted())),
+            Policy::RoundRobin => Box::new(RoundRobin::default()),
+            Policy::Srtf => Box::new(Srtf::new()),
+            Policy::Priority => Box::new(Priority::new()),
+            Policy::PriorityPreemptive => Box::new(Priority::preemptive()),
+            Policy::Lottery => Box::new(Lottery::default()),
+            Policy::Stride => Box::new(Stride::new()),
+            Policy::Cfs => Box::new(Cfs::new()),
+            Policy::Eevdf => Box::new(Eevdf::new()),
+            Policy::Hrrn => Box::new(Hrrn::new()),
+            Policy::Edf => Box::new(Edf::new()),
+            Policy::Llf => Box::new(Llf::new()),
+            Policy::RateMonotonic => Box::new(FixedPriority::rate_monotonic()),
+            Policy::DeadlineMonotonic => Box::new(FixedPriority::deadline_monotonic()),
+        }
+    }
+
+    /// Simulate `processes` with a new scheduler implementing this policy.
+    pub fn run(self, processes: VecDeque<Process>) -> Result<SimulationResult, SimError> {
+        self.run_with(processes, &SimulationConfig::default(), &mut [])
+    }
+
+    /// Simulate `processes` with a new scheduler implementing this policy, on
+    /// the machine described by `config`, delivering every event to `observers`.
+    pub fn run_with(
+        self,
+        processes: VecDeque<Process>,
+        config: &SimulationConfig,
+        observers: &mut [&mut dyn Observer],
+    ) -> Result<SimulationResult, SimError> {
+        simulate_with(self.scheduler().as_mut(), processes, config, observers)
+    }
+}
@@ -0,0 +1,451 @@
+use super::{Context, Migration, Overrun, Scheduler};
+use crate::process::Process;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Ready queue(s) per CPU, as Linux keeps them: every CPU has a scheduler of its
+/// own, built by the same function, and runs the processes queued on it. A new
+/// process joins the CPU with the least load, ready and running processes, and a
+/// process goes back to the CPU it last ran on, so it mostly stays on one CPU.
+///
+/// Queues drift apart as processes come and go. A periodic load balancer moves
+/// ready processes from the busiest CPU to the least busy one until their loads are
+/// at most one apart, and a CPU with nothing to run steals a ready process from the
+/// CPU with the most waiting. Either way the process moved is the one its scheduler
+/// would have run next, and it keeps its standing with the scheduler, e.g. its level
+/// or virtual runtime, see [`Scheduler::take_migrating`]. A moved process that ran
+/// before pays the migration cost of the machine, if any, when it gets on the new CPU.
+///
+/// Balancing happens at the first scheduling decision once it is due, as the
+/// scheduler has no clock of its own.
+pub struct PerCpu {
+    name: String,
+    queues: Vec<Box<dyn Scheduler>>,
+    labels: Vec<Vec<String>>,
+    // CPU every running process was picked for, by name.
+    running: HashMap<String, usize>,
+    balance_interval: Option<i32>,
+    // Global clock of the next load balancing, if any.
+    next_balance: Option<i32>,
+    stealing: bool,
+}
+
+impl PerCpu {
+    /// Time between two load balancing passes unless set otherwise.
+    pub const DEFAULT_BALANCE_INTERVAL: i32 = 20;
+
+    /// New scheduler for `cpus` CPUs, each with empty ready queue(s) of a scheduler
+    /// built by `scheduler`. Balances load every [`PerCpu::DEFAULT_BALANCE_INTERVAL`]
+    /// time units and lets idle CPUs steal work.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cpus` is 0.
+    pub fn new(cpus: usize, mut scheduler: impl FnMut() -> Box<dyn Scheduler>) -> PerCpu {
+        assert!(cpus > 0, "need a run queue for at least one CPU");
+        let queues: Vec<Box<dyn Scheduler>> = (0..cpus).map(|_| scheduler()).collect();
+        PerCpu {
+            name: format!("{} (per-CPU run queues)", queues[0].name()),
+            labels: queues
+                .iter()
+                .enumerate()
+                .map(|(cpu, queue)| {
+                    queue
+                        .queues()
+                        .iter()
+                        .map(|(label, _)| format!("CPU {} {}", cpu, label))
+                        .collect()
+                })
+                .collect(),
+            queues,
+            running: HashMap::new(),
+            balance_interval: Some(PerCpu::DEFAULT_BALANCE_INTERVAL),
+            next_balance: Some(PerCpu::DEFAULT_BALANCE_INTERVAL),
+            stealing: true,
+        }
+    }
+
+    /// Balance load every `interval` time units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is not positive.
+    pub fn with_balance_interval(mut self, interval: i32) -> PerCpu {
+        assert!(interval > 0, "balance interval must be positive");
+        self.balance_interval = Some(interval);
+        self.next_balance = Some(interval);
+        self
+    }
+
+    /// Never balance load periodically.
+    pub fn without_balancing(mut self) -> PerCpu {
+        self.balance_interval = None;
+        self.next_balance = None;
+        self
+    }
+
+    /// Leave CPUs with nothing to run idle, rather than steal work.
+    pub fn without_stealing(mut self) -> PerCpu {
+        self.stealing = false;
+        self
+    }
+
+    fn ready(&self, cpu: usize) -> usize {
+        self.queues[cpu]
+            .queues()
+            .iter()
+            .map(|(_, queue)| queue.len())
+            .sum()
+    }
+
+    fn loads(&self) -> Vec<usize> {
+        /*
+            Ready and running processes of every CPU.
+        */
+        let mut loads: Vec<usize> = (0..self.queues.len()).map(|cpu| self.ready(cpu)).collect();
+        for &cpu in self.running.values() {
+            loads[cpu] += 1;
+        }
+        loads
+    }
+
+    fn placement(&self, process: &Process) -> usize {
+        /*
+            CPU the process joins: the one it last ran on, or the one with the least
+            load, the first one on ties.
+        */
+        match process.last_cpu {
+            Some(cpu) if cpu < self.queues.len() => cpu,
+            _ => {
+                let loads = self.loads();
+                (0..loads.len()).min_by_key(|&cpu| loads[cpu]).unwrap_or(0)
+            }
+        }
+    }
+
+    fn migrate(&mut self, from: usize, to: usize, ctx: &Context) -> bool {
+        /*
+            Move the process the scheduler of `from` would run next to `to`. False if
+            `from` has no process ready.
+        */
+        let (process, migration) = match self.queues[from].take_migrating(ctx) {
+            Some(taken) => taken,
+            None => return false,
+        };
+        self.queues[to].admit_migrated(process, migration, ctx);
+        true
+    }
+
+    fn balance(&mut self, ctx: &Context) {
+        /*
+            Move ready processes from the busiest CPU to the least busy one until
+            their loads are at most one apart, once balancing is due.
+        */
+        let (interval, next_balance) = match (self.balance_interval, self.next_balance) {
+            (Some(interval), Some(next_balance)) if next_balance <= ctx.global_clock() => {
+                (interval, next_balance)
+            }
+            _ => return,
+        };
+        let periods = (ctx.global_clock() - next_balance) / interval + 1;
+        self.next_balance = Some(next_balance + periods * interval);
+
+        loop {
+            let loads = self.loads();
+            let busiest = (0..loads.len()).max_by_key(|&cpu| (loads[cpu], std::cmp::Reverse(cpu)));
+            let idlest = (0..loads.len()).min_by_key(|&cpu| loads[cpu]);
+            match (busiest, idlest) {
+                (Some(busiest), Some(idlest)) if loads[busiest] > loads[idlest] + 1 => {
+                    if !self.migrate(busiest, idlest, ctx) {
+                        return;
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn steal(&mut self, cpu: usize, ctx: &Context) {
+        /*
+            Take a ready process for `cpu` from the CPU with the most waiting.
+        */
+        if let Some(victim) = self.most_waiting(Some(cpu)) {
+            self.migrate(victim, cpu, ctx);
+        }
+    }
+
+    fn most_waiting(&self, except: Option<usize>) -> Option<usize> {
+        // CPU with the most ready processes other than `except`, if any has one.
+        (0..self.queues.len())
+            .filter(|&other| Some(other) != except)
+            .map(|other| (self.ready(other), other))
+            .filter(|&(ready, _)| ready > 0)
+            .max_by_key(|&(ready, other)| (ready, std::cmp::Reverse(other)))
+            .map(|(_, cpu)| cpu)
+    }
+
+    fn cpu_of(&self, process: &Process) -> usize {
+        // Processes handed back were picked for a CPU, fall back on the first one.
+        self.running.get(&process.name).copied().unwrap_or(0)
+    }
+}
+
+impl Scheduler for PerCpu {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn on_ready(&mut self, process: Process, ctx: &Context) {
+        self.balance(ctx);
+        let cpu = self.placement(&process);
+        self.queues[cpu].on_ready(process, ctx);
+    }
+
+    fn pick_next(&mut self, ctx: &Context) -> Option<(Process, i32)> {
+        // CPUs without a run queue of their own stay idle.
+        self.balance(ctx);
+        let cpu = ctx.cpu().unwrap_or(0);
+        if cpu >= self.queues.len() {
+            return None;
+        }
+        if self.stealing && self.ready(cpu) == 0 {
+            self.steal(cpu, ctx);
+        }
+
+        let (process, time_quanta) = self.queues[cpu].pick_next(ctx)?;
+        self.running.insert(process.name.clone(), cpu);
+        Some((process, time_quanta))
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        self.balance(ctx);
+        let cpu = self.cpu_of(&process);
+        self.running.remove(&process.name);
+        self.queues[cpu].on_quantum_expired(process, ctx);
+    }
+
+    fn preempts(&self, running: &Process, remaining: i32, ready: &Process) -> bool {
+        // Only the process running on the CPU `ready` joins.
+        let cpu = self.cpu_of(running);
+        cpu == self.placement(ready) && self.queues[cpu].preempts(running, remaining, ready)
+    }
+
+    fn cut_slice(&self, running: &Process, remaining: i32, ready: &Process) -> Option<i32> {
+        let cpu = self.cpu_of(running);
+        if cpu != self.placement(ready) {
+            return None;
+        }
+        self.queues[cpu].cut_slice(running, remaining, ready)
+    }
+
+    fn on_preempted(&mut self, process: Process, ctx: &Context) {
+        let cpu = self.cpu_of(&process);
+        self.running.remove(&process.name);
+        self.queues[cpu].on_preempted(process, ctx);
+    }
+
+    fn on_burst_complete(&mut self, process: &Process, ctx: &Context) {
+        let cpu = self.cpu_of(process);
+        self.running.remove(&process.name);
+        self.queues[cpu].on_burst_complete(process, ctx);
+    }
+
+    fn take_migrating(&mut self, ctx: &Context) -> Option<(Process, Migration)> {
+        // From the CPU with the most waiting.
+        let cpu = self.most_waiting(None)?;
+        self.queues[cpu].take_migrating(ctx)
+    }
+
+    fn admit_migrated(&mut self, process: Process, migration: Migration, ctx: &Context) {
+        let cpu = self.placement(&process);
+        self.queues[cpu].admit_migrated(process, migration, ctx);
+    }
+
+    fn io_return_order(&self, a: &Process, b: &Process) -> Ordering {
+        self.queues[0].io_return_order(a, b)
+    }
+
+    fn overrun(&self) -> Overrun {
+        self.queues[0].overrun()
+    }
+
+    fn share_weights(&self, processes: &[Process]) -> Option<Vec<f64>> {
+        self.queues[0].share_weights(processes)
+    }
+
+    fn cpu_loads(&self) -> Option<Vec<usize>> {
+        Some((0..self.queues.len()).map(|cpu| self.ready(cpu)).collect())
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        self.labels
+            .iter()
+            .zip(self.queues.iter())
+            .flat_map(|(labels, queue)| {
+                labels
+                    .iter()
+                    .zip(queue.queues())
+                    .map(|(label, (_, processes))| (label.as_str(), processes))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::{simulate, simulate_with, SimulationConfig};
+    use crate::observer::{EventLog, SimEvent};
+    use crate::scheduler::{Fcfs, Mlfq, Policy, RoundRobin};
+    use crate::workload::default_workload;
+    use std::cell::RefCell;
+    use std::collections::{BTreeMap, VecDeque};
+
+    #[test]
+    fn idle_cpus_steal_from_per_cpu_queues() {
+        let config = SimulationConfig {
+            cpus: 2,
+            migration_cost: 1,
+            ..Default::default()
+        };
+        let processes = || {
+            VecDeque::from(vec![
+                Process::new("P1", Bursts::cpu(1).io(0, 1).cpu(3)),
+                Process::new("P2", Bursts::cpu(4)),
+                Process::new("P3", Bursts::cpu(5)).with_arrival_time(1),
+            ])
+        };
+
+        // P1 runs 0-1 on CPU 0 and P2 0-4 on CPU 1. P3 joins CPU 0, the least
+        // loaded, and runs 1-6, so P1 is back at 2 to a busy CPU 0. CPU 1 steals it
+        // at 4 and it runs 5-8, after the migration.
+        let mut log = EventLog::default();
+        let mut scheduler = PerCpu::new(2, || Box::new(Fcfs::new()));
+        let result = simulate_with(&mut scheduler, processes(), &config, &mut [&mut log]).unwrap();
+        assert_eq!(result.global_clock, 8);
+        assert_eq!(result.metrics[0].migrations, 1);
+        assert_eq!(result.metrics[0].turnaround_time, 7);
+        assert_eq!(result.migrations(), 1);
+        assert_eq!(result.cpus[1].switch_overhead, 1);
+        assert!(log.events.contains(&SimEvent::Migrate {
+            time: 4,
+            process: "P1".to_string(),
+            from: 0,
+            to: 1
+        }));
+
+        // Without stealing P1 waits for P3 and runs 6-9.
+        let mut scheduler = PerCpu::new(2, || Box::new(Fcfs::new())).without_stealing();
+        let result = simulate_with(&mut scheduler, processes(), &config, &mut []).unwrap();
+        assert_eq!(result.global_clock, 9);
+        assert_eq!(result.metrics[0].migrations, 0);
+        assert_eq!(result.metrics[0].turnaround_time, 8);
+    }
+
+    #[test]
+    fn load_balancing_evens_per_cpu_queues() {
+        let config = SimulationConfig {
+            cpus: 2,
+            ..Default::default()
+        };
+        let processes = || {
+            VecDeque::from(vec![
+                Process::new("P1", Bursts::cpu(12)),
+                Process::new("P2", Bursts::cpu(1)),
+                Process::new("P3", Bursts::cpu(12)),
+                Process::new("P4", Bursts::cpu(1)),
+            ])
+        };
+
+        // P1 and P3 share CPU 0, P2 and P4 are done on CPU 1 at 2. The balancer
+        // moves P3 to CPU 1 at 5, P1 then runs 5-12 and P3 5-17 side by side. P3
+        // had not run yet, so that is no migration.
+        let mut log = EventLog::default();
+        let mut scheduler = PerCpu::new(2, || Box::new(RoundRobin::new(5)))
+            .with_balance_interval(5)
+            .without_stealing();
+        let result = simulate_with(&mut scheduler, processes(), &config, &mut [&mut log]).unwrap();
+        assert_eq!(result.global_clock, 17);
+        assert!(log.events.contains(&SimEvent::Dispatch {
+            time: 5,
+            cpu: 1,
+            process: "P3".to_string(),
+            time_quanta: 5,
+            switch_cost: 0
+        }));
+        assert_eq!(result.migrations(), 0);
+        assert!(!log
+            .events
+            .iter()
+            .any(|event| matches!(event, SimEvent::Migrate { .. })));
+        let loads: Vec<(i32, Vec<usize>)> = result
+            .loads
+            .iter()
+            .map(|sample| (sample.time, sample.loads.clone()))
+            .collect();
+        assert_eq!(
+            loads,
+            vec![
+                (0, vec![2, 2]),
+                (1, vec![2, 1]),
+                (2, vec![2, 0]),
+                (5, vec![1, 1]),
+                (12, vec![0, 1]),
+                (17, vec![0, 0]),
+            ]
+        );
+        assert_eq!(result.average_imbalance(), 12.0 / 17.0);
+
+        // Left alone, CPU 0 runs both until 24 while CPU 1 idles from 2.
+        let mut scheduler = PerCpu::new(2, || Box::new(RoundRobin::new(5)))
+            .without_balancing()
+            .without_stealing();
+        let result = simulate_with(&mut scheduler, processes(), &config, &mut []).unwrap();
+        assert_eq!(result.global_clock, 24);
+        assert_eq!(result.average_imbalance(), 43.0 / 24.0);
+    }
+
+    #[test]
+    fn queues_on_one_cpu_match_the_scheduler() {
+        for policy in Policy::ALL.iter() {
+            let single = policy.run(default_workload()).unwrap();
+            let mut scheduler = PerCpu::new(1, || policy.scheduler());
+            let result = simulate(&mut scheduler, default_workload()).unwrap();
+            assert_eq!(
+                result.global_clock,
+                single.global_clock,
+                "{}",
+                policy.label()
+            );
+            assert_eq!(result.metrics, single.metrics, "{}", policy.label());
+        }
+    }
+
+    #[test]
+    fn migrated_processes_keep_their_mlfq_level() {
+        let io_queue = BTreeMap::new();
+        let emitted = RefCell::new(Vec::new());
+        let ctx = || Context::new(0, &io_queue, &emitted);
+        let mut scheduler = PerCpu::new(2, || Box::new(Mlfq::new())).without_balancing();
+
+        // Demoted to the second level on CPU 0.
+        let mut process = Process::new("P1", Bursts::cpu(40));
+        process.last_cpu = Some(0);
+        scheduler.on_ready(process, &ctx());
+        let (mut process, time_quanta) = scheduler.pick_next(&ctx().on_cpu(0)).unwrap();
+        assert_eq!(time_quanta, 5);
+        process.last_cpu = Some(0);
+        scheduler.on_quantum_expired(process, &ctx());
+
+        // Stolen by CPU 1, it runs the time-quanta of the second level, and moves
+        // down to the third.
+        let (process, time_quanta) = scheduler.pick_next(&ctx().on_cpu(1)).unwrap();
+        assert_eq!(time_quanta, 10);
+        scheduler.on_quantum_expired(process, &ctx());
+        let queues = scheduler.queues();
+        let (label, queue) = queues.iter().find(|(_, queue)| !queue.is_empty()).unwrap();
+        assert_eq!(*label, "CPU 1 Level 3 SJF");
+        assert_eq!(queue[0].name, "P1");
+    }
+}
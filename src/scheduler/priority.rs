@@ -0,0 +1,159 @@
+use super::{Context, Scheduler};
+use crate::process::Process;
+use std::collections::VecDeque;
+
+/// Priority scheduling, the ready process with the lowest priority number runs its
+/// whole CPU burst, ties are broken first come first serve.
+///
+/// In preemptive mode a process that arrives or returns from IO with a better
+/// priority than the running process takes the CPU from it.
+///
+/// With aging, a process gains one priority level per `interval` time units it
+/// spends waiting in the ready queue, so low priority processes do not starve.
+/// It falls back to its own priority once it gets on the CPU.
+pub struct Priority {
+    ready_queue: VecDeque<Process>,
+    preemptive: bool,
+    aging_interval: Option<i32>,
+}
+
+impl Priority {
+    /// New non-preemptive scheduler with empty ready queue(s) and no aging.
+    pub fn new() -> Priority {
+        Priority {
+            ready_queue: VecDeque::new(),
+            preemptive: false,
+            aging_interval: None,
+        }
+    }
+
+    /// New preemptive scheduler with empty ready queue(s) and no aging.
+    pub fn preemptive() -> Priority {
+        Priority {
+            preemptive: true,
+            ..Priority::new()
+        }
+    }
+
+    /// Raise the priority of waiting processes by one level every `interval`
+    /// time units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is not positive.
+    pub fn with_aging(mut self, interval: i32) -> Priority {
+        assert!(interval > 0, "aging interval must be positive");
+        self.aging_interval = Some(interval);
+        self
+    }
+
+    fn effective_priority(&self, process: &Process, global_clock: i32) -> i32 {
+        /*
+            Priority of the process after aging for the time it has been waiting.
+        */
+        match (self.aging_interval, process.ready_since) {
+            (Some(interval), Some(ready_since)) => {
+                process.params.priority - (global_clock - ready_since) / interval
+            }
+            _ => process.params.priority,
+        }
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Priority {
+        Priority::new()
+    }
+}
+
+impl Scheduler for Priority {
+    fn name(&self) -> &str {
+        if self.preemptive {
+            "Preemptive Priority"
+        } else {
+            "Priority"
+        }
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        // Kept in the order processes became ready, which breaks ties.
+        self.ready_queue.push_back(process);
+    }
+
+    fn pick_next(&mut self, ctx: &Context) -> Option<(Process, i32)> {
+        let (index, _) = self
+            .ready_queue
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, process)| self.effective_priority(process, ctx.global_clock()))?;
+        let process = self.ready_queue.remove(index)?;
+
+        // Time-quanta is the whole of the current burst. A process without one
+        // fails when the engine runs it.
+        let process_quanta = process.current_burst().unwrap_or(0);
+        Some((process, process_quanta))
+    }
+
+    fn preempts(&self, running: &Process, _remaining: i32, ready: &Process) -> bool {
+        // The process just became ready, it has not aged yet.
+        self.preemptive && ready.params.priority < running.params.priority
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        // Cannot happen as the whole burst is granted, put the process back in line.
+        self.on_ready(process, ctx);
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Priority", self.ready_queue.iter().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::simulate;
+    use crate::process::SchedulingParams;
+
+    #[test]
+    fn runs_lowest_number_first() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("P1", Bursts::cpu(4)).with_params(SchedulingParams {
+                    priority: 2,
+                    ..Default::default()
+                }),
+                Process::new("P2", Bursts::cpu(3)).with_params(SchedulingParams {
+                    priority: 2,
+                    ..Default::default()
+                }),
+                Process::new("P3", Bursts::cpu(2))
+                    .with_params(SchedulingParams {
+                        priority: 1,
+                        ..Default::default()
+                    })
+                    .with_arrival_time(1),
+            ])
+        };
+
+        // P1 0-4, P3 4-6, P2 6-9.
+        let result = simulate(&mut Priority::new(), workload()).unwrap();
+        assert_eq!(result.global_clock, 9);
+        assert_eq!(result.metrics[1].longest_wait, 6);
+        assert_eq!(result.metrics[2].longest_wait, 3);
+        assert_eq!(result.longest_wait(), 6);
+
+        // P1 0-1, P3 1-3, P2 3-6 as it has been waiting longer, P1 6-9.
+        let result = simulate(&mut Priority::preemptive(), workload()).unwrap();
+        assert_eq!(result.preemptions, 1);
+        assert_eq!(result.metrics[0].longest_wait, 5);
+        assert_eq!(result.metrics[2].longest_wait, 0);
+
+        // Every 2 units of waiting raise a process one level, at 4 P2 (waited 4) and
+        // P3 (waited 3) are both at level 0 and P2 came first. P1 0-4, P2 4-7, P3 7-9.
+        let result = simulate(&mut Priority::new().with_aging(2), workload()).unwrap();
+        assert_eq!(result.metrics[1].longest_wait, 4);
+        assert_eq!(result.metrics[2].longest_wait, 6);
+    }
+}
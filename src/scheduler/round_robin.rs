@@ -0,0 +1,100 @@
+use super::{Context, Scheduler};
+use crate::process::Process;
+use std::collections::VecDeque;
+
+/// Preemptive Round-Robin, every process runs for at most one time-quanta before it
+/// goes to the back of the ready queue.
+///
+/// The time-quanta is the scheduler's own unless the process sets its own in
+/// [`SchedulingParams::time_quanta`](crate::SchedulingParams::time_quanta).
+pub struct RoundRobin {
+    ready_queue: VecDeque<Process>,
+    time_quanta: i32,
+}
+
+impl RoundRobin {
+    /// Time-quanta of the Round-Robin entry of the main menu.
+    pub const DEFAULT_QUANTA: i32 = 5;
+
+    /// New scheduler with empty ready queue(s), giving every process
+    /// `time_quanta` units at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time_quanta` is not positive, as no process would ever progress.
+    pub fn new(time_quanta: i32) -> RoundRobin {
+        assert!(time_quanta > 0, "time-quanta must be positive");
+        RoundRobin {
+            ready_queue: VecDeque::new(),
+            time_quanta,
+        }
+    }
+}
+
+impl Default for RoundRobin {
+    fn default() -> RoundRobin {
+        RoundRobin::new(RoundRobin::DEFAULT_QUANTA)
+    }
+}
+
+impl Scheduler for RoundRobin {
+    fn name(&self) -> &str {
+        "Round-Robin"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        self.ready_queue.push_back(process);
+    }
+
+    fn pick_next(&mut self, _ctx: &Context) -> Option<(Process, i32)> {
+        let process = self.ready_queue.pop_front()?;
+
+        // A process that asks for a non-positive time-quanta gets the default
+        // one, unused time of a short burst is handed back by `Process::run`.
+        let process_quanta = process
+            .params
+            .time_quanta
+            .filter(|&quanta| quanta > 0)
+            .unwrap_or(self.time_quanta);
+        Some((process, process_quanta))
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, _ctx: &Context) {
+        self.ready_queue.push_back(process);
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Round-Robin", self.ready_queue.iter().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::simulate;
+    use crate::process::SchedulingParams;
+
+    #[test]
+    fn rotates_processes() {
+        let processes = VecDeque::from(vec![
+            Process::new("P1", Bursts::cpu(5)),
+            Process::new("P2", Bursts::cpu(2)).with_params(SchedulingParams {
+                time_quanta: Some(1),
+                ..Default::default()
+            }),
+        ]);
+
+        // P1 0-2, P2 2-3, P1 3-5, P2 5-6 done, P1 6-7 done.
+        let result = simulate(&mut RoundRobin::new(2), processes).unwrap();
+        assert_eq!(result.global_clock, 7);
+        assert_eq!(result.context_switches, 5);
+        assert_eq!(result.metrics[0].turnaround_time, 6);
+        assert_eq!(result.metrics[1].turnaround_time, 5);
+
+        // A single process keeps the CPU across its time-quanta.
+        let processes = VecDeque::from(vec![Process::new("P1", Bursts::cpu(5))]);
+        let result = simulate(&mut RoundRobin::new(2), processes).unwrap();
+        assert_eq!(result.context_switches, 1);
+    }
+}
@@ -0,0 +1,50 @@
+use super::{Context, Scheduler};
+use crate::process::Process;
+use sorts::quick_sort::quick_sort;
+use std::collections::VecDeque;
+
+/// Non-preemptive Shortest Job First.
+///
+/// Invariant of this scheduler is that the ready queue is always sorted by current
+/// CPU burst. The queue is sorted any time a new process is inserted into it.
+#[derive(Default)]
+pub struct Sjf {
+    ready_queue: VecDeque<Process>,
+}
+
+impl Sjf {
+    /// New scheduler with empty ready queue(s).
+    pub fn new() -> Sjf {
+        Default::default()
+    }
+}
+
+impl Scheduler for Sjf {
+    fn name(&self) -> &str {
+        "Shortest Job First"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        // Sort processes by CPU burst size to maintain invariant.
+        self.ready_queue.push_back(process);
+        quick_sort(self.ready_queue.make_contiguous());
+    }
+
+    fn pick_next(&mut self, _ctx: &Context) -> Option<(Process, i32)> {
+        let process = self.ready_queue.pop_front()?;
+
+        // Time-quanta is the whole of the current burst. A process without one
+        // fails when the engine runs it.
+        let process_quanta = process.current_burst().unwrap_or(0);
+        Some((process, process_quanta))
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        // Cannot happen as the whole burst is granted, put the process back in line.
+        self.on_ready(process, ctx);
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Process", self.ready_queue.iter().collect())]
+    }
+}
@@ -0,0 +1,102 @@
+use super::{Context, Scheduler};
+use crate::process::Process;
+use sorts::quick_sort::quick_sort;
+use std::collections::VecDeque;
+
+/// Shortest Remaining Time First, the preemptive version of Shortest Job First. A
+/// process that arrives or returns from IO with a CPU burst shorter than what the
+/// running process has left of its own takes the CPU from it.
+///
+/// Invariant of this scheduler is that the ready queue is always sorted by current
+/// CPU burst, which is the remaining time of preempted processes.
+#[derive(Default)]
+pub struct Srtf {
+    ready_queue: VecDeque<Process>,
+}
+
+impl Srtf {
+    /// New scheduler with empty ready queue(s).
+    pub fn new() -> Srtf {
+        Default::default()
+    }
+}
+
+impl Scheduler for Srtf {
+    fn name(&self) -> &str {
+        "Shortest Remaining Time First"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        // Sort processes by CPU burst size to maintain invariant.
+        self.ready_queue.push_back(process);
+        quick_sort(self.ready_queue.make_contiguous());
+    }
+
+    fn pick_next(&mut self, _ctx: &Context) -> Option<(Process, i32)> {
+        let process = self.ready_queue.pop_front()?;
+
+        // Time-quanta is the rest of the current burst, unless preempted. A process
+        // without one fails when the engine runs it.
+        let process_quanta = process.current_burst().unwrap_or(0);
+        Some((process, process_quanta))
+    }
+
+    fn preempts(&self, _running: &Process, remaining: i32, ready: &Process) -> bool {
+        // Ties keep the running process on the CPU.
+        ready.current_burst().is_some_and(|burst| burst < remaining)
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, ctx: &Context) {
+        // Cannot happen as the whole burst is granted, put the process back in line.
+        self.on_ready(process, ctx);
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Process", self.ready_queue.iter().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::{simulate_with, SimulationConfig};
+    use crate::observer::{EventLog, SimEvent};
+    use crate::scheduler::Policy;
+
+    #[test]
+    fn preempts_on_io_return() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("P1", Bursts::cpu(1).io(0, 2).cpu(2)),
+                Process::new("P2", Bursts::cpu(10)).with_arrival_time(1),
+            ])
+        };
+
+        // P1 0-1, P2 1-3, P1 back from IO at 3 with 2 units against 8 takes over
+        // until 5, then P2 runs its remaining 8 units.
+        let mut log = EventLog::default();
+        let result = simulate_with(
+            &mut Srtf::new(),
+            workload(),
+            &SimulationConfig::default(),
+            &mut [&mut log],
+        )
+        .unwrap();
+        assert_eq!(result.global_clock, 13);
+        assert_eq!(result.preemptions, 1);
+        assert_eq!(result.context_switches, 4);
+        assert_eq!(result.metrics[0].turnaround_time, 4);
+        assert_eq!(result.metrics[1].turnaround_time, 11);
+        assert!(log.events.contains(&SimEvent::Preempt {
+            time: 3,
+            process: "P2".to_string(),
+            by: "P1".to_string()
+        }));
+
+        // Non-preemptive schedulers never preempt.
+        let result = Policy::Sjf.run(workload()).unwrap();
+        assert_eq!(result.global_clock, 13);
+        assert_eq!(result.preemptions, 0);
+    }
+}
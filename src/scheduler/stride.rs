@@ -0,0 +1,245 @@
+use super::{Context, Migration, Scheduler};
+use crate::process::Process;
+use std::collections::{HashMap, VecDeque};
+
+// Stride of a process with a single ticket.
+const STRIDE_ONE: i64 = 1 << 20;
+
+/// Stride scheduling, the deterministic counterpart of [`Lottery`](super::Lottery).
+/// Every process has a stride inversely proportional to its tickets and a pass
+/// value, the ready process with the lowest pass runs a time-quanta and advances
+/// its pass by its stride (less if it gives up the CPU early). Ties go to the
+/// process that has been ready the longest.
+///
+/// A global pass advances with the CPU time given out, by the stride of all
+/// tickets in play. A process that leaves for IO keeps how far its pass was from
+/// the global pass and picks up at that same distance when it rejoins, so it
+/// neither makes up for the time away nor loses its place.
+pub struct Stride {
+    ready_queue: VecDeque<Process>,
+    time_quanta: i32,
+    // Pass value of every ready or running process.
+    pass: HashMap<String, i64>,
+    // Distance to the global pass of processes in IO.
+    remain: HashMap<String, i64>,
+    global_pass: i64,
+    // Processes dispatched and not back yet, by name.
+    running: HashMap<String, Running>,
+}
+
+#[derive(Default)]
+struct Running {
+    // Tickets of the process, and tickets in play when it was dispatched.
+    tickets: i64,
+    in_play: i64,
+    // CPU time of the time slice handed out.
+    slice: i32,
+}
+
+impl Stride {
+    /// Time-quanta of every dispatch unless set otherwise.
+    pub const DEFAULT_QUANTA: i32 = 5;
+
+    /// New scheduler with empty ready queue(s).
+    pub fn new() -> Stride {
+        Stride {
+            ready_queue: VecDeque::new(),
+            time_quanta: Stride::DEFAULT_QUANTA,
+            pass: HashMap::new(),
+            remain: HashMap::new(),
+            global_pass: 0,
+            running: HashMap::new(),
+        }
+    }
+
+    /// Dispatch processes `time_quanta` units at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time_quanta` is not positive.
+    pub fn with_time_quanta(mut self, time_quanta: i32) -> Stride {
+        assert!(time_quanta > 0, "time-quanta must be positive");
+        self.time_quanta = time_quanta;
+        self
+    }
+
+    fn stride(tickets: i64) -> i64 {
+        // Processes without tickets count as holding one.
+        STRIDE_ONE / tickets.max(1)
+    }
+
+    fn advance(&mut self, process: &Process) {
+        /*
+            Charge the time slice of the process to its pass and the global pass, in
+            proportion to the part of the time-quanta it used.
+        */
+        let running = self.running.remove(&process.name).unwrap_or_default();
+        let used = running.slice as i64;
+        let quanta = self.time_quanta as i64;
+        *self
+            .pass
+            .entry(process.name.clone())
+            .or_insert(self.global_pass) +=
+            Stride::stride(process.params.tickets as i64) * used / quanta;
+        self.global_pass += Stride::stride(running.in_play) * used / quanta;
+    }
+
+    fn lowest_pass(&self) -> Option<usize> {
+        // Index of the ready process with the lowest pass, the first one on ties.
+        self.ready_queue
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, process)| self.pass[&process.name])
+            .map(|(index, _)| index)
+    }
+}
+
+impl Default for Stride {
+    fn default() -> Stride {
+        Stride::new()
+    }
+}
+
+impl Scheduler for Stride {
+    fn name(&self) -> &str {
+        "Stride"
+    }
+
+    fn on_ready(&mut self, process: Process, _ctx: &Context) {
+        // New processes start a stride ahead of the global pass.
+        let remain = match self.remain.remove(&process.name) {
+            Some(remain) => remain,
+            None => Stride::stride(process.params.tickets as i64),
+        };
+        self.pass
+            .insert(process.name.clone(), self.global_pass + remain);
+        self.ready_queue.push_back(process);
+    }
+
+    fn pick_next(&mut self, _ctx: &Context) -> Option<(Process, i32)> {
+        let index = self.lowest_pass()?;
+
+        // Tickets of the ready processes and those running on other CPUs.
+        let in_play = self
+            .ready_queue
+            .iter()
+            .map(|process| process.params.tickets as i64)
+            .chain(self.running.values().map(|running| running.tickets))
+            .sum();
+        let process = self.ready_queue.remove(index)?;
+
+        // A process without a CPU burst fails when the engine runs it.
+        self.running.insert(
+            process.name.clone(),
+            Running {
+                tickets: process.params.tickets as i64,
+                in_play,
+                slice: self.time_quanta.min(process.current_burst().unwrap_or(0)),
+            },
+        );
+        Some((process, self.time_quanta))
+    }
+
+    fn on_quantum_expired(&mut self, process: Process, _ctx: &Context) {
+        self.advance(&process);
+        self.ready_queue.push_back(process);
+    }
+
+    fn on_burst_complete(&mut self, process: &Process, _ctx: &Context) {
+        // Keep the distance to the global pass for when the process is back from IO.
+        self.advance(process);
+        if let Some(pass) = self.pass.remove(&process.name) {
+            self.remain
+                .insert(process.name.clone(), pass - self.global_pass);
+        }
+    }
+
+    fn take_migrating(&mut self, _ctx: &Context) -> Option<(Process, Migration)> {
+        // The distance to the global pass goes along, as it does to IO.
+        let process = self.ready_queue.remove(self.lowest_pass()?)?;
+        let remain = self.pass.remove(&process.name).unwrap_or(self.global_pass) - self.global_pass;
+        Some((process, Migration::new(remain)))
+    }
+
+    fn admit_migrated(&mut self, process: Process, migration: Migration, ctx: &Context) {
+        if let Some(remain) = migration.into_state::<i64>() {
+            self.remain.insert(process.name.clone(), remain);
+        }
+        self.on_ready(process, ctx);
+    }
+
+    fn share_weights(&self, processes: &[Process]) -> Option<Vec<f64>> {
+        Some(
+            processes
+                .iter()
+                .map(|process| process.params.tickets as f64)
+                .collect(),
+        )
+    }
+
+    fn queues(&self) -> Vec<(&str, Vec<&Process>)> {
+        vec![("Stride", self.ready_queue.iter().collect())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burst::Bursts;
+    use crate::engine::simulate;
+    use crate::process::SchedulingParams;
+
+    #[test]
+    fn shares_follow_tickets() {
+        let workload = || {
+            VecDeque::from(vec![
+                Process::new("P1", Bursts::cpu(1000)).with_params(SchedulingParams {
+                    tickets: 300,
+                    ..Default::default()
+                }),
+                Process::new("P2", Bursts::cpu(1000)),
+                Process::new("P3", Bursts::cpu(1000)),
+            ])
+        };
+
+        let result = simulate(&mut Stride::new(), workload()).unwrap();
+        for share in result.shares.iter() {
+            assert!((share.cpu_share - share.entitled_share).abs() < 0.01);
+        }
+
+        // Errors shrink as the CPU time adds up.
+        let first = &result.share_errors[0];
+        let last = result.share_errors.last().unwrap();
+        assert!(first.time < last.time);
+        let worst = |errors: &[f32]| errors.iter().fold(0f32, |worst, e| worst.max(e.abs()));
+        assert!(worst(&last.errors) < worst(&first.errors));
+
+        // Nothing random about it.
+        let again = simulate(&mut Stride::new(), workload()).unwrap();
+        assert_eq!(again.share_errors, result.share_errors);
+    }
+
+    #[test]
+    fn rejoins_after_io_at_the_same_distance() {
+        let workload = VecDeque::from(vec![
+            Process::new("P1", Bursts::cpu(50).io(0, 200).cpu(300)),
+            Process::new("P2", Bursts::cpu(1000)),
+        ]);
+
+        // Back from IO, P1 takes turns with P2 rather than catching up on the time
+        // it was away, so its last burst takes about twice its length.
+        let result = simulate(&mut Stride::new(), workload).unwrap();
+        let p1 = &result.metrics[0];
+        assert_eq!(p1.name, "P1");
+        assert!(
+            p1.turnaround_time > 100 + 200 + 550,
+            "{}",
+            p1.turnaround_time
+        );
+        assert!(
+            p1.turnaround_time < 100 + 200 + 650,
+            "{}",
+            p1.turnaround_time
+        );
+    }
+}
@@ -0,0 +1,451 @@
+//! Building and validating workloads.
+
+use crate::burst::{Burst, Bursts};
+use crate::error::{InvalidWorkload, Problem, WorkloadError};
+use crate::periodic::{self, PeriodicTask};
+use crate::process::{Process, SchedulingParams};
+use std::collections::{HashSet, VecDeque};
+
+/// Unchecked description of a process, e.g. as read from user input. Turn a
+/// list of them into processes with [`build`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessSpec {
+    /// Name of the process, unique within the workload.
+    pub name: String,
+    /// Time the process enters the ready queue.
+    pub arrival_time: i32,
+    /// Bursts of the process, which should start and end with a CPU burst and
+    /// alternate CPU and IO bursts.
+    pub bursts: Vec<Burst>,
+    /// Time-quanta of the process, see [`SchedulingParams::time_quanta`].
+    pub time_quanta: Option<i32>,
+    /// Priority of the process, see [`SchedulingParams::priority`].
+    pub priority: i32,
+    /// Lottery tickets of the process, see [`SchedulingParams::tickets`].
+    pub tickets: u32,
+    /// Group the process belongs to, see [`SchedulingParams::group`].
+    pub group: Option<String>,
+    /// Nice value of the process, see [`SchedulingParams::nice`].
+    pub nice: i32,
+    /// Deadline of the whole job, see [`SchedulingParams::deadline`].
+    pub deadline: Option<i32>,
+    /// Deadlines of the CPU bursts, none or one per CPU burst, see
+    /// [`SchedulingParams::burst_deadlines`].
+    pub burst_deadlines: Vec<i32>,
+}
+
+impl Default for ProcessSpec {
+    fn default() -> ProcessSpec {
+        ProcessSpec {
+            name: String::new(),
+            arrival_time: 0,
+            bursts: Vec::new(),
+            time_quanta: None,
+            priority: 0,
+            tickets: Process::DEFAULT_TICKETS,
+            group: None,
+            nice: 0,
+            deadline: None,
+            burst_deadlines: Vec::new(),
+        }
+    }
+}
+
+impl ProcessSpec {
+    /// Process named `name` arriving at time 0 with the default tickets, from a
+    /// flat list of burst lengths where even positions are CPU bursts and odd
+    /// positions IO bursts on device 0.
+    pub fn alternating(name: &str, bursts: &[i32]) -> ProcessSpec {
+        ProcessSpec {
+            name: name.to_string(),
+            bursts: bursts
+                .iter()
+                .enumerate()
+                .map(|(index, &duration)| {
+                    if index % 2 == 0 {
+                        Burst::Cpu(duration)
+                    } else {
+                        Burst::Io {
+                            device: 0,
+                            duration,
+                        }
+                    }
+                })
+                .collect(),
+            ..ProcessSpec::default()
+        }
+    }
+}
+
+/// Check every process of `specs` and report every problem found at once.
+pub fn validate(specs: &[ProcessSpec]) -> Result<(), InvalidWorkload> {
+    let mut errors: Vec<WorkloadError> = Vec::new();
+    let mut names: HashSet<&str> = HashSet::new();
+
+    for spec in specs.iter() {
+        let mut report = |burst: Option<usize>, problem: Problem| {
+            errors.push(WorkloadError {
+                process: spec.name.clone(),
+                burst,
+                problem,
+            })
+        };
+
+        if !names.insert(&spec.name) {
+            report(None, Problem::DuplicateName);
+        }
+        if spec.arrival_time < 0 {
+            report(None, Problem::NegativeArrival(spec.arrival_time));
+        }
+        if let Some(time_quanta) = spec.time_quanta.filter(|&quanta| quanta <= 0) {
+            report(None, Problem::NonPositiveQuanta(time_quanta));
+        }
+
+        let cpu_bursts = spec.bursts.iter().filter(|burst| burst.is_cpu()).count();
+        if !spec.burst_deadlines.is_empty() && spec.burst_deadlines.len() != cpu_bursts {
+            report(
+                None,
+                Problem::DeadlineCount {
+                    expected: cpu_bursts,
+                    found: spec.burst_deadlines.len(),
+                },
+            );
+        }
+
+        let last = match spec.bursts.len() {
+            0 => {
+                report(None, Problem::NoBursts);
+                continue;
+            }
+            len => len - 1,
+        };
+        if !spec.bursts[0].is_cpu() {
+            report(Some(0), Problem::StartsWithIo);
+        }
+
+        // The process finishes at the latest at its arrival plus all of its bursts.
+        let mut finish = Some(spec.arrival_time);
+        for (index, burst) in spec.bursts.iter().enumerate() {
+            if index > 0 && burst.is_cpu() == spec.bursts[index - 1].is_cpu() {
+                report(Some(index), Problem::NotAlternating);
+            }
+            if burst.duration() <= 0 {
+                report(Some(index), Problem::NonPositiveBurst(burst.duration()));
+            }
+
+            // Only report where the total first overflows.
+            let total = finish.and_then(|time| time.checked_add(burst.duration()));
+            if finish.is_some() && total.is_none() {
+                report(Some(index), Problem::Overflow);
+            }
+            finish = total;
+        }
+
+        // Reported after the problems of the other bursts, which all come before it.
+        if !spec.bursts[last].is_cpu() {
+            report(Some(last), Problem::EndsWithIo);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(InvalidWorkload { errors })
+    }
+}
+
+/// Validate `specs` and turn them into processes ready to be simulated, in the
+/// same order.
+pub fn build(specs: &[ProcessSpec]) -> Result<VecDeque<Process>, InvalidWorkload> {
+    validate(specs)?;
+
+    Ok(specs
+        .iter()
+        .map(|spec| {
+            let params = SchedulingParams {
+                time_quanta: spec.time_quanta,
+                priority: spec.priority,
+                tickets: spec.tickets,
+                group: spec.group.clone(),
+                nice: spec.nice,
+                deadline: spec.deadline,
+                burst_deadlines: spec.burst_deadlines.iter().copied().collect(),
+                ..Default::default()
+            };
+            Process::new(&spec.name, Bursts::from_phases(spec.bursts.clone()))
+                .with_params(params)
+                .with_arrival_time(spec.arrival_time)
+        })
+        .collect())
+}
+
+/// The eight processes of the original assignment, all activated at time 0.
+/// Bursts alternate CPU and IO, starting and ending with a CPU burst.
+pub fn default_workload() -> VecDeque<Process> {
+    VecDeque::from(vec![
+        Process::new(
+            "P1",
+            Bursts::alternating(
+                5,
+                &[
+                    (27, 3),
+                    (31, 5),
+                    (43, 4),
+                    (18, 6),
+                    (22, 4),
+                    (26, 3),
+                    (24, 5),
+                ],
+            ),
+        ),
+        Process::new(
+            "P2",
+            Bursts::alternating(
+                4,
+                &[
+                    (48, 5),
+                    (44, 7),
+                    (42, 12),
+                    (37, 9),
+                    (76, 4),
+                    (41, 9),
+                    (31, 7),
+                    (43, 8),
+                ],
+            ),
+        ),
+        Process::new(
+            "P3",
+            Bursts::alternating(
+                8,
+                &[
+                    (33, 12),
+                    (41, 18),
+                    (65, 14),
+                    (21, 4),
+                    (61, 15),
+                    (18, 14),
+                    (26, 5),
+                    (31, 6),
+                ],
+            ),
+        ),
+        Process::new(
+            "P4",
+            Bursts::alternating(
+                3,
+                &[
+                    (35, 4),
+                    (41, 5),
+                    (45, 3),
+                    (51, 4),
+                    (61, 5),
+                    (54, 6),
+                    (82, 5),
+                    (77, 3),
+                ],
+            ),
+        ),
+        Process::new(
+            "P5",
+            Bursts::alternating(
+                16,
+                &[
+                    (24, 17),
+                    (21, 5),
+                    (36, 16),
+                    (26, 7),
+                    (31, 13),
+                    (28, 11),
+                    (21, 6),
+                    (13, 3),
+                    (11, 4),
+                ],
+            ),
+        ),
+        Process::new(
+            "P6",
+            Bursts::alternating(
+                11,
+                &[
+                    (22, 4),
+                    (8, 5),
+                    (10, 6),
+                    (12, 7),
+                    (14, 9),
+                    (18, 12),
+                    (24, 15),
+                    (30, 8),
+                ],
+            ),
+        ),
+        Process::new(
+            "P7",
+            Bursts::alternating(
+                14,
+                &[
+                    (46, 17),
+                    (41, 11),
+                    (42, 15),
+                    (21, 4),
+                    (32, 7),
+                    (19, 16),
+                    (33, 10),
+                ],
+            ),
+        ),
+        Process::new(
+            "P8",
+            Bursts::alternating(4, &[(14, 5), (33, 6), (51, 14), (73, 16), (87, 6)]),
+        ),
+    ])
+}
+
+/// Two processes that game a Multi-Level Feedback Queue, next to two CPU bound
+/// processes, all activated at time 0.
+///
+/// The gamers ("G1" and "G2") run 4 units at a time and give up the CPU for a
+/// single unit of IO just before a time-quanta of 5 runs out, which keeps them on
+/// the first level of [`MlfqConfig::classic`](crate::MlfqConfig::classic) for good
+/// and leaves the CPU bound processes ("H1" and "H2") waiting until they are done.
+/// With [`MlfqConfig::allotted`](crate::MlfqConfig::allotted) the gamers move down
+/// like everyone else once they used up their allotment.
+pub fn gaming_workload() -> VecDeque<Process> {
+    let gamer = [(1, 4); 20];
+    VecDeque::from(vec![
+        Process::new("G1", Bursts::alternating(4, &gamer)),
+        Process::new("G2", Bursts::alternating(4, &gamer)),
+        Process::new("H1", Bursts::cpu(60)),
+        Process::new("H2", Bursts::cpu(60)),
+    ])
+}
+
+/// Workload of soft real-time processes along with batch jobs, more than the CPU
+/// can keep up with.
+///
+/// A video decoder ("V") decodes a frame every 10 units, each due 9 units after
+/// its release, and an audio process ("A") fills a buffer every 5 units, each due
+/// by the next release. A build ("C") is due at 90 and a backup ("B") at 200.
+pub fn deadline_workload() -> VecDeque<Process> {
+    VecDeque::from(vec![
+        Process::new("V", Bursts::alternating(4, &[(6, 4); 9])).with_params(SchedulingParams {
+            burst_deadlines: (0..10).map(|frame| frame * 10 + 9).collect(),
+            ..Default::default()
+        }),
+        Process::new("A", Bursts::alternating(2, &[(3, 2); 19])).with_params(SchedulingParams {
+            burst_deadlines: (1..=20).map(|buffer| buffer * 5).collect(),
+            ..Default::default()
+        }),
+        Process::new("C", Bursts::cpu(40)).with_params(SchedulingParams {
+            deadline: Some(90),
+            ..Default::default()
+        }),
+        Process::new("B", Bursts::cpu(60)).with_params(SchedulingParams {
+            deadline: Some(200),
+            ..Default::default()
+        }),
+    ])
+}
+
+/// Periodic tasks of a small controller that fit on the CPU under Deadline
+/// Monotonic but not under Rate Monotonic.
+///
+/// A sensor ("Sensor") is read every 5 units, a control loop ("Control") runs every
+/// 10 units and has to be done within 3 units of its release, and a logger
+/// ("Logger") writes every 20 units from time 2 on.
+pub fn periodic_task_set() -> Vec<PeriodicTask> {
+    vec![
+        PeriodicTask::new("Sensor", 5, 1),
+        PeriodicTask {
+            deadline: 3,
+            ..PeriodicTask::new("Control", 10, 3)
+        },
+        PeriodicTask {
+            offset: 2,
+            ..PeriodicTask::new("Logger", 20, 6)
+        },
+    ]
+}
+
+/// Jobs [`periodic_task_set`] releases over two hyperperiods past the last offset.
+pub fn periodic_workload() -> VecDeque<Process> {
+    let tasks = periodic_task_set();
+    let horizon = tasks.iter().map(|task| task.offset).max().unwrap_or(0)
+        + 2 * periodic::hyperperiod(&tasks).unwrap_or(0);
+    periodic::release_jobs(&tasks, horizon).expect("built-in task set is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_derives_totals() {
+        let specs = vec![ProcessSpec {
+            arrival_time: 4,
+            ..ProcessSpec::alternating("P1", &[5, 27, 3])
+        }];
+
+        let processes = build(&specs).unwrap();
+
+        assert_eq!(processes[0].total_process_time, 35);
+        assert_eq!(processes[0].arrival_time, 4);
+        assert_eq!(*processes[0].bursts(), Bursts::alternating(5, &[(27, 3)]));
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        let specs = vec![
+            ProcessSpec::alternating("P1", &[5, 0, 3, 2]),
+            ProcessSpec {
+                bursts: vec![Burst::Cpu(i32::MAX), Burst::Cpu(1)],
+                ..ProcessSpec::alternating("P2", &[])
+            },
+            ProcessSpec::alternating("P1", &[-4]),
+            ProcessSpec::alternating("P3", &[]),
+            ProcessSpec {
+                time_quanta: Some(0),
+                ..ProcessSpec::alternating("P4", &[1])
+            },
+            ProcessSpec {
+                burst_deadlines: vec![10],
+                ..ProcessSpec::alternating("P5", &[1, 2, 3])
+            },
+        ];
+
+        let error = |process: &str, burst, problem| WorkloadError {
+            process: process.to_string(),
+            burst,
+            problem,
+        };
+        assert_eq!(
+            validate(&specs).unwrap_err().errors,
+            vec![
+                error("P1", Some(1), Problem::NonPositiveBurst(0)),
+                error("P1", Some(3), Problem::EndsWithIo),
+                error("P2", Some(1), Problem::NotAlternating),
+                error("P2", Some(1), Problem::Overflow),
+                error("P1", None, Problem::DuplicateName),
+                error("P1", Some(0), Problem::NonPositiveBurst(-4)),
+                error("P3", None, Problem::NoBursts),
+                error("P4", None, Problem::NonPositiveQuanta(0)),
+                error(
+                    "P5",
+                    None,
+                    Problem::DeadlineCount {
+                        expected: 2,
+                        found: 1
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn default_workload_totals() {
+        // Totals the original assignment spelled out by hand.
+        let processes = default_workload();
+        assert_eq!(processes[0].total_process_time, 226);
+        assert_eq!(processes[7].total_process_time, 309);
+    }
+}